@@ -2,9 +2,15 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use clap::{command, value_parser, Arg, ArgAction, ArgGroup, Command, ValueHint};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    command, value_parser, Arg, ArgAction, ArgGroup, Command, ValueHint,
+};
 use const_format::formatcp;
-use osh_dir_std::{constants::PROJECT_ISSUES_URL, data::STD_NAMES};
+use osh_dir_std::{
+    badge, constants::PROJECT_ISSUES_URL, data::STD_NAMES, interchange::StdFormat,
+    listing::ListingFormat, rules,
+};
 use regex::Regex;
 use std::env;
 
@@ -23,6 +29,11 @@ pub const A_S_QUIET: char = 'q';
 pub const A_L_INPUT_LISTING: &str = "listing";
 pub const A_S_INPUT_LISTING: char = 'I';
 
+pub const A_L_LISTING_FORMAT: &str = "listing-format";
+
+pub const A_L_NULL: &str = "null";
+pub const A_S_NULL: char = 'z';
+
 pub const SC_N_MAP: &str = "map";
 
 pub const A_L_STANDARD: &str = "standard";
@@ -31,15 +42,104 @@ pub const A_S_STANDARD: char = 's';
 pub const A_L_BEST_FIT: &str = "best-fit";
 pub const A_S_BEST_FIT: char = 'b';
 
+pub const A_L_MIN_FIT: &str = "min-fit";
+pub const A_L_MIN_MARGIN: &str = "min-margin";
+pub const A_L_NO_FALLBACK: &str = "no-default-fallback";
+
 pub const A_L_ALL: &str = "all";
 pub const A_S_ALL: char = 'a';
 
 pub const A_L_IGNORE_PATHS: &str = "ignore-paths-regex";
 pub const A_S_IGNORE_PATHS: char = 'i';
 
+pub const A_L_TAGS: &str = "tags";
+
+pub const A_L_RULE: &str = "rule";
+
+pub const A_L_RULES_FILE: &str = "rules-file";
+
 pub const A_L_INCLUDE_COVERAGE: &str = "include-coverage";
 pub const A_S_INCLUDE_COVERAGE: char = 'c';
 
+pub const A_L_CASE_INSENSITIVE: &str = "case-insensitive";
+pub const A_L_NFC: &str = "nfc";
+pub const A_L_NORMALIZE_SEPARATORS: &str = "normalize-separators";
+
+pub const A_L_STREAM: &str = "stream";
+
+pub const SC_N_DIFF: &str = "diff";
+
+pub const A_L_OLD_LISTING: &str = "old-listing";
+pub const A_L_NEW_LISTING: &str = "new-listing";
+pub const A_L_OLD_REV: &str = "old-rev";
+pub const A_L_NEW_REV: &str = "new-rev";
+
+pub const SC_N_LINT_STD: &str = "lint-std";
+
+pub const SC_N_SHOW: &str = "show";
+
+pub const SC_N_DRIFT: &str = "drift";
+
+pub const A_L_DECLARED_IN: &str = "declared-in";
+pub const A_L_MARGIN: &str = "margin";
+
+pub const SC_N_HOOK: &str = "hook";
+
+pub const SC_N_INSTALL_HOOK: &str = "install-hook";
+
+pub const A_L_FORCE: &str = "force";
+
+pub const SC_N_CHECK: &str = "check";
+
+pub const A_L_CHANGED_SINCE: &str = "changed-since";
+pub const A_L_PROJECT_LISTING: &str = "project-listing";
+
+pub const SC_N_ANNOTATE: &str = "annotate";
+
+pub const SC_N_BADGE: &str = "badge";
+
+pub const A_L_LABEL: &str = "label";
+pub const A_L_GOOD: &str = "good";
+pub const A_L_FAIR: &str = "fair";
+pub const A_L_MODULES_DIR: &str = "modules-dir";
+
+#[cfg(feature = "serve")]
+pub const SC_N_SERVE: &str = "serve";
+
+#[cfg(feature = "serve")]
+pub const A_L_BIND: &str = "bind";
+
+#[cfg(feature = "tui")]
+pub const SC_N_TUI: &str = "tui";
+
+pub const A_L_STD_FILE: &str = "std-file";
+
+pub const A_L_STD_CSV: &str = "std-csv";
+
+pub const A_L_FORMAT: &str = "format";
+pub const A_S_FORMAT: char = 'f';
+
+pub const FORMAT_JSON: &str = "json";
+pub const FORMAT_TEXT: &str = "text";
+pub const FORMAT_NDJSON: &str = "ndjson";
+pub const FORMAT_CSV: &str = "csv";
+pub const FORMAT_JUNIT: &str = "junit";
+pub const FORMAT_GITLAB: &str = "gitlab";
+pub const FORMAT_GITHUB: &str = "github";
+pub const FORMAT_SARIF: &str = "sarif";
+pub const FORMAT_DOT: &str = "dot";
+pub const FORMAT_MERMAID: &str = "mermaid";
+
+#[allow(clippy::indexing_slicing)]
+const SHOW_FORMATS: [&str; 6] = [
+    StdFormat::NAMES[0],
+    StdFormat::NAMES[1],
+    StdFormat::NAMES[2],
+    StdFormat::NAMES[3],
+    FORMAT_DOT,
+    FORMAT_MERMAID,
+];
+
 fn arg_output() -> Arg {
     Arg::new(A_P_OUTPUT)
         .help("The output file")
@@ -99,6 +199,86 @@ or '-' or no argument, meaning the same format is expected on stdin.",
         .global(true)
 }
 
+fn arg_listing_format() -> Arg {
+    Arg::new(A_L_LISTING_FORMAT)
+        .help("The format of the input listing(s)")
+        .long_help(
+            "The format of the input listing(s): \
+'lines' - new-line separated paths, skipping empty lines and lines starting with '#'; \
+'null' - NUL ('\\0') separated paths, as produced by `git ls-files -z` or `find -print0`; \
+'json-lines' - one JSON object per line, with at least a \"path\" field; \
+further per-entry metadata (e.g. \"kind\" or \"size\") is accepted.",
+        )
+        .long(A_L_LISTING_FORMAT)
+        .alias("lst-fmt")
+        .num_args(1)
+        .value_parser(
+            PossibleValuesParser::new(ListingFormat::NAMES)
+                .try_map(|name| name.parse::<ListingFormat>()),
+        )
+        .value_name("FORMAT")
+        .conflicts_with(A_L_NULL)
+        .action(ArgAction::Set)
+        .global(true)
+}
+
+fn arg_null() -> Arg {
+    Arg::new(A_L_NULL)
+        .help("Input listing(s) are NUL-separated")
+        .long_help(
+            "Input listing(s) are NUL ('\\0') separated, \
+as produced by `git ls-files -z` or `find -print0`. \
+This allows for any characters in paths, including new-lines, \
+and no comments are recognized. \
+Same as '--listing-format null'.",
+        )
+        .short(A_S_NULL)
+        .long(A_L_NULL)
+        .alias("nul")
+        .action(ArgAction::SetTrue)
+        .global(true)
+}
+
+fn arg_case_insensitive() -> Arg {
+    Arg::new(A_L_CASE_INSENSITIVE)
+        .help("Match paths regardless of upper-/lower-case")
+        .long_help(
+            "Match paths against the standard regardless of upper-/lower-case, \
+so e.g. 'Doc/' or 'README.MD' are recognized as 'doc/' and 'README.md'. \
+Does not apply to the ignore paths regex.",
+        )
+        .long(A_L_CASE_INSENSITIVE)
+        .alias("ci")
+        .action(ArgAction::SetTrue)
+        .global(true)
+}
+
+fn arg_nfc() -> Arg {
+    Arg::new(A_L_NFC)
+        .help("Unicode NFC-normalize paths before matching")
+        .long_help(
+            "Brings paths and the standards regexes \
+into Unicode Normalization Form C (NFC) before matching, \
+so decomposed characters (as commonly produced on macOS) \
+match their composed forms.",
+        )
+        .long(A_L_NFC)
+        .action(ArgAction::SetTrue)
+        .global(true)
+}
+
+fn arg_normalize_separators() -> Arg {
+    Arg::new(A_L_NORMALIZE_SEPARATORS)
+        .help("Treat '\\' as a path separator")
+        .long_help(
+            "Treat '\\' (as used on Windows) as a path separator, \
+by converting it to '/' before matching.",
+        )
+        .long(A_L_NORMALIZE_SEPARATORS)
+        .action(ArgAction::SetTrue)
+        .global(true)
+}
+
 fn arg_include_coverage() -> Arg {
     Arg::new(A_L_INCLUDE_COVERAGE)
         .help("Includes the coverage")
@@ -107,19 +287,445 @@ fn arg_include_coverage() -> Arg {
         .action(ArgAction::SetTrue)
 }
 
+fn arg_stream() -> Arg {
+    Arg::new(A_L_STREAM)
+        .help("Process the listing in (about) constant memory")
+        .long_help(
+            "Process the listing in (about) constant memory, \
+by not keeping the paths, but only aggregate counters for the rating. \
+This allows to process huge listings. \
+With 'map', the result for each path is written out as NDJSON \
+(one JSON object per line) right after it was classified. \
+NOTE: Duplicate input paths and ancestor dirs are only detected \
+if all the content of each dir is listed consecutively, \
+as is the case with the output of `git ls-files` or `find`.",
+        )
+        .long(A_L_STREAM)
+        .alias("streaming")
+        .action(ArgAction::SetTrue)
+}
+
 fn subcom_rate() -> Command {
     Command::new(SC_N_RATE)
         .about("Rates a project repo directory with all known OSH dir standards, indicating for each standard how well it fits")
-        .arg(arg_include_coverage())
+        .arg(arg_include_coverage().conflicts_with(A_L_STREAM))
+        .arg(arg_stream())
         .alias("r")
 }
 
 fn subcom_map() -> Command {
     Command::new(SC_N_MAP)
         .about("Maps project directories and files to parts of the standard")
+        .arg(arg_stream())
+        .arg(
+            arg_format(&[
+                FORMAT_JSON,
+                FORMAT_NDJSON,
+                FORMAT_CSV,
+                FORMAT_DOT,
+                FORMAT_MERMAID,
+            ])
+            .long_help(
+                "The output format: \
+'json' - the paths grouped under the matching records, per standard; \
+with --stream, one full mapping JSON object per path and line; \
+'ndjson' - one flat JSON object per path and line; \
+'csv' - one row per path; \
+'dot' (Graphviz) and 'mermaid' - a graph per standard, \
+with the (top-most) paths attached to the records they match. \
+The flat formats have the columns: \
+standard, path, status, records, module, tags; \
+multiple values within one column are separated by '|'.",
+            ),
+        )
         .alias("m")
 }
 
+fn arg_listing_of(name: &'static str, which: &str) -> Arg {
+    Arg::new(name)
+        .help(format!("Dirs and files listing of the {which} state"))
+        .long_help(format!(
+            "Dirs and files listing of the {which} state of the project. \
+Same format as for --{A_L_INPUT_LISTING}."
+        ))
+        .long(name)
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .action(ArgAction::Set)
+}
+
+fn arg_rev_of(name: &'static str, which: &str) -> Arg {
+    Arg::new(name)
+        .help(format!("Git revision of the {which} state"))
+        .long_help(format!(
+            "Git revision of the {which} state of the project; \
+all files tracked in it are used as the listing. \
+Requires the current working directory to be within the git repo."
+        ))
+        .long(name)
+        .num_args(1)
+        .value_name("REV")
+        .action(ArgAction::Set)
+}
+
+fn arg_format(formats: &'static [&'static str]) -> Arg {
+    #[allow(clippy::indexing_slicing)]
+    Arg::new(A_L_FORMAT)
+        .help("The output format")
+        .short(A_S_FORMAT)
+        .long(A_L_FORMAT)
+        .num_args(1)
+        .value_parser(formats.to_vec())
+        .default_value(formats[0])
+        .value_name("FORMAT")
+        .action(ArgAction::Set)
+}
+
+fn subcom_diff() -> Command {
+    Command::new(SC_N_DIFF)
+        .about("Shows how the coverage by the standard(s) changed between two listings or git revisions")
+        .arg(arg_listing_of(A_L_OLD_LISTING, "old").conflicts_with(A_L_OLD_REV))
+        .arg(arg_listing_of(A_L_NEW_LISTING, "new").conflicts_with(A_L_NEW_REV))
+        .arg(arg_rev_of(A_L_OLD_REV, "old"))
+        .arg(arg_rev_of(A_L_NEW_REV, "new"))
+        .arg(arg_format(&[FORMAT_JSON, FORMAT_TEXT]))
+        .group(
+            ArgGroup::new("grp_old")
+                .args([A_L_OLD_LISTING, A_L_OLD_REV])
+                .required(true),
+        )
+        .group(
+            ArgGroup::new("grp_new")
+                .args([A_L_NEW_LISTING, A_L_NEW_REV])
+                .required(true),
+        )
+        .alias("d")
+}
+
+fn arg_std_csv() -> Arg {
+    Arg::new(A_L_STD_CSV)
+        .help("Directory standard definition CSV file(s) to check")
+        .long_help(
+            "Directory standard definition CSV file(s) to check, \
+in the format used in the osh-dir-std repo \
+(e.g. 'mod/unixish/definition.csv').",
+        )
+        .long(A_L_STD_CSV)
+        .num_args(1..)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("CSV-FILE")
+        .value_hint(ValueHint::FilePath)
+        .required(true)
+        .action(ArgAction::Append)
+}
+
+fn subcom_lint_std() -> Command {
+    Command::new(SC_N_LINT_STD)
+        .about("Checks directory standard definition(s) for problems, reporting them with CSV line numbers")
+        .long_about(
+            "Checks directory standard definition(s) for problems: \
+invalid or overlapping regexes, \
+records with both or neither variations and regex, \
+missing parent records, zero or negative indicativeness, \
+duplicate paths, unknown tags \
+and module or generated flags on files. \
+Exits with a non-zero code if any problem was found.",
+        )
+        .arg(arg_std_csv())
+        .arg(arg_format(&[FORMAT_TEXT, FORMAT_JSON]))
+        .alias("lint")
+}
+
+fn arg_std_file() -> Arg {
+    Arg::new(A_L_STD_FILE)
+        .help("Read the standard from this file, instead of using a built-in one")
+        .long_help(
+            "Read the standard from this file, instead of using a built-in one. \
+The format is guessed from the file extension \
+(.csv, .json, .yaml/.yml or .toml). \
+This allows to convert a standard between the formats.",
+        )
+        .long(A_L_STD_FILE)
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .action(ArgAction::Set)
+}
+
+fn subcom_show() -> Command {
+    Command::new(SC_N_SHOW)
+        .about("Prints a directory standard, for example to convert it to an other format")
+        .long_about(
+            "Prints a directory standard (the default one, or the one given with --standard), \
+for example to convert it to an other format, edit it there, \
+and convert it back with --std-file. \
+The formats 'dot' (Graphviz) and 'mermaid' print the record hierarchy as a graph, \
+e.g. for documentation; they can not be read back.",
+        )
+        .arg(arg_std_file())
+        .arg(arg_format(&SHOW_FORMATS))
+        .alias("s")
+}
+
+fn arg_declared_in() -> Arg {
+    Arg::new(A_L_DECLARED_IN)
+        .help("Read the declared standard from this TOML file, unless --standard is given")
+        .long_help(formatcp!(
+            "Read the declared standard from this TOML file \
+(e.g. the projects okh.toml), unless --{A_L_STANDARD} is given. \
+The standard is declared like this:\n\
+\n\
+[osh-dir-std]\n\
+standard = \"unixish\"\n"
+        ))
+        .long(A_L_DECLARED_IN)
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .default_value("okh.toml")
+        .value_name("TOML-FILE")
+        .value_hint(ValueHint::FilePath)
+        .action(ArgAction::Set)
+}
+
+fn arg_margin() -> Arg {
+    Arg::new(A_L_MARGIN)
+        .help("By how much an other standard has to fit better than the declared one, to count as drift")
+        .long(A_L_MARGIN)
+        .num_args(1)
+        .value_parser(value_parser!(f32))
+        .default_value("0.05")
+        .value_name("FACTOR")
+        .action(ArgAction::Set)
+}
+
+fn subcom_drift() -> Command {
+    Command::new(SC_N_DRIFT)
+        .about("Checks whether an other standard now fits the project better than the declared one")
+        .long_about(
+            "Rates the listing with all known standards, \
+and checks whether an other standard now fits the project better \
+than the one it declares to follow, by at least --margin. \
+Reports the scores of both, \
+and exits with a non-zero code if the project drifted.",
+        )
+        .arg(arg_declared_in())
+        .arg(arg_margin())
+        .arg(arg_format(&[FORMAT_TEXT, FORMAT_JSON]))
+}
+
+fn subcom_hook() -> Command {
+    Command::new(SC_N_HOOK)
+        .about("Checks only the paths staged in git, as a pre-commit hook")
+        .long_about(formatcp!(
+            "Checks only the paths added to the git index (staging area), \
+and fails if any of them is out of the standard, \
+or matches a record that should not be tracked. \
+Deviations of already committed paths are left alone. \
+Uses the standard given with --{A_L_STANDARD}, \
+or else the one declared in okh.toml, \
+or else the default one. \
+See also '{SC_N_INSTALL_HOOK}'."
+        ))
+        .arg(arg_format(&[FORMAT_TEXT, FORMAT_JSON]))
+}
+
+fn arg_force() -> Arg {
+    Arg::new(A_L_FORCE)
+        .help("Overwrite an existing pre-commit hook")
+        .long(A_L_FORCE)
+        .action(ArgAction::SetTrue)
+}
+
+fn subcom_install_hook() -> Command {
+    Command::new(SC_N_INSTALL_HOOK)
+        .about(formatcp!(
+            "Installs a git pre-commit hook that runs '{SC_N_HOOK}'"
+        ))
+        .arg(arg_force())
+}
+
+fn arg_changed_since() -> Arg {
+    Arg::new(A_L_CHANGED_SINCE)
+        .help("Check the paths added since the given git revision (e.g. 'origin/main')")
+        .long_help(formatcp!(
+            "Check the paths added, copied or renamed since \
+the given git revision (e.g. 'origin/main'), \
+or rather since HEAD branched off from it. \
+If not given, the changes are read from the input listing \
+(see --{A_L_INPUT_LISTING}), \
+in the format of `git diff --name-status`, \
+or of `git diff --name-status -z` if --{A_L_NULL} is given; \
+the latter is required for paths that git quotes, \
+e.g. those containing non-ASCII characters."
+        ))
+        .long(A_L_CHANGED_SINCE)
+        .num_args(1)
+        .value_name("REV")
+        .action(ArgAction::Set)
+}
+
+fn arg_project_listing() -> Arg {
+    arg_listing_of(A_L_PROJECT_LISTING, "current").long_help(formatcp!(
+        "Dirs and files listing of the whole project, \
+including the changes. \
+Same format as for --{A_L_INPUT_LISTING}. \
+If not given, all files tracked by git at HEAD are used."
+    ))
+}
+
+fn subcom_check() -> Command {
+    Command::new(SC_N_CHECK)
+        .about("Checks only the paths added in a change (e.g. a PR), within the whole project")
+        .long_about(formatcp!(
+            "Checks only the paths added in a change (e.g. a PR), \
+but within the context of the whole project \
+(as tracked by git at HEAD, or as given by --{A_L_PROJECT_LISTING}), \
+so module boundaries resolve as in a full check. \
+Reports the added paths that do not fit the standard, \
+together with the record they most likely belong to, \
+and exits with a non-zero code if there are any. \
+Violations of house rules (see --{A_L_RULE}) are reported as well, \
+if they concern an added path or a dir containing one. \
+Uses the same standard as '{SC_N_HOOK}'."
+        ))
+        .arg(arg_changed_since())
+        .arg(arg_project_listing())
+        .arg(arg_format(&[FORMAT_TEXT, FORMAT_JSON]))
+}
+
+fn subcom_annotate() -> Command {
+    Command::new(SC_N_ANNOTATE)
+        .about("Reports deviations from the standard(s) in formats CI systems understand")
+        .arg(
+            arg_format(&[
+                FORMAT_JUNIT,
+                FORMAT_GITLAB,
+                FORMAT_GITHUB,
+                FORMAT_SARIF,
+                FORMAT_TEXT,
+            ])
+            .long_help(
+                "The output format: \
+'junit' - JUnit XML, with one test case per normative record \
+and per path that is out of the standard or should not be tracked; \
+'gitlab' - GitLab Code Quality JSON; \
+'github' - GitHub workflow commands (`::warning file=...::...`); \
+'sarif' - SARIF 2.1.0, e.g. for GitHub code scanning; \
+'text' - one line per problem",
+            ),
+        )
+        .alias("ci")
+}
+
+fn arg_label() -> Arg {
+    Arg::new(A_L_LABEL)
+        .help("The text on the left side of the badge")
+        .long(A_L_LABEL)
+        .num_args(1)
+        .default_value(badge::DEFAULT_LABEL)
+        .value_name("TEXT")
+        .action(ArgAction::Set)
+}
+
+fn arg_badge_threshold(name: &'static str, color: &'static str, default: &'static str) -> Arg {
+    Arg::new(name)
+        .help(format!(
+            "From which rating factor on the badge is {color} [default: {default}]"
+        ))
+        .long(name)
+        .num_args(1)
+        .value_parser(value_parser!(f32))
+        .value_name("FACTOR")
+        .action(ArgAction::Set)
+}
+
+fn arg_modules_dir() -> Arg {
+    Arg::new(A_L_MODULES_DIR)
+        .help("Also write one badge per module into this directory")
+        .long_help(
+            "Also write one badge per module into this directory, \
+named after the module path, with '/' replaced by '-' \
+(e.g. 'mod-motor.svg').",
+        )
+        .long(A_L_MODULES_DIR)
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("DIR")
+        .value_hint(ValueHint::DirPath)
+        .action(ArgAction::Set)
+}
+
+fn subcom_badge() -> Command {
+    Command::new(SC_N_BADGE)
+        .about("Renders a self-contained SVG badge showing the rating")
+        .long_about(formatcp!(
+            "Renders a self-contained, shields.io style SVG badge \
+showing the rating of the listing with the standard, \
+e.g. \"dir-std | unixish 87%\", \
+colored green, yellow or red according to --{A_L_GOOD} and --{A_L_FAIR}. \
+Does not work with --{A_L_ALL}."
+        ))
+        .arg(arg_label())
+        .arg(arg_badge_threshold(A_L_GOOD, "green", "0.8"))
+        .arg(arg_badge_threshold(A_L_FAIR, "yellow", "0.5"))
+        .arg(arg_modules_dir())
+}
+
+#[cfg(feature = "serve")]
+fn arg_bind() -> Arg {
+    Arg::new(A_L_BIND)
+        .help("The address (and port) to listen on")
+        .long(A_L_BIND)
+        .num_args(1)
+        .default_value(crate::serve::DEFAULT_BIND)
+        .value_name("ADDRESS")
+        .action(ArgAction::Set)
+}
+
+#[cfg(feature = "serve")]
+fn subcom_serve() -> Command {
+    Command::new(SC_N_SERVE)
+        .about("Runs a local HTTP service for rating, mapping and checking listings")
+        .long_about(formatcp!(
+            "Runs a local HTTP service for rating, mapping and checking listings, \
+with the standards loaded only once. \
+Endpoints: \
+'GET /standards', \
+'GET /standards/{{name}}', \
+'POST /rate', \
+'POST /map' and \
+'POST /check', \
+the later three taking a listing as request body \
+(new-line separated, or JSON with Content-Type application/json \
+or application/x-ndjson), \
+and an optional query parameter 'standard' \
+(a standard name, 'all' or 'best-fit'), \
+which overrides --{A_L_STANDARD}, --{A_L_ALL} and --{A_L_BEST_FIT}."
+        ))
+        .arg(arg_bind())
+}
+
+#[cfg(feature = "tui")]
+fn subcom_tui() -> Command {
+    Command::new(SC_N_TUI)
+        .about("Browses the coverage of a listing interactively, in the terminal")
+        .long_about(formatcp!(
+            "Browses the coverage of a listing interactively, in the terminal. \
+Shows the project tree, coloured by the category of each path \
+(in, module, out, ignored, arbitrary content or generated content), \
+next to the record tree of a standard, \
+highlighting the records the selected path matches, \
+with their descriptions below. \
+The listing is checked against all the standards, \
+which can be switched with Tab and Shift+Tab. \
+The standard shown first is the one chosen with --{A_L_STANDARD}, \
+or the best fitting one with --{A_L_ALL} or --{A_L_BEST_FIT}."
+        ))
+}
+
 fn arg_standard() -> Arg {
     Arg::new(A_L_STANDARD)
         .help("Which OSH directory standard to check coverage for or rate")
@@ -150,6 +756,60 @@ fn arg_best_fit() -> Arg {
         .global(true)
 }
 
+fn arg_min_fit() -> Arg {
+    Arg::new(A_L_MIN_FIT)
+        .help("Minimum factor for a best fit to be viable [default: 0.2]")
+        .long_help(formatcp!(
+            "The minimum factor the best fitting standard needs to have, \
+to be considered viable at all. \
+Only used with --{A_L_BEST_FIT}. \
+[default: 0.2]"
+        ))
+        .long(A_L_MIN_FIT)
+        .num_args(1)
+        .value_parser(value_parser!(f32))
+        .value_name("FACTOR")
+        .requires(A_L_BEST_FIT)
+        .action(ArgAction::Set)
+        .global(true)
+}
+
+fn arg_min_margin() -> Arg {
+    Arg::new(A_L_MIN_MARGIN)
+        .help("Minimum lead of the best fit over the runner-up [default: 0.05]")
+        .long_help(formatcp!(
+            "The minimum difference between the factors \
+of the best fitting and the runner-up standard, \
+for the best fit to be considered unambiguous. \
+Only used with --{A_L_BEST_FIT}. \
+[default: 0.05]"
+        ))
+        .long(A_L_MIN_MARGIN)
+        .num_args(1)
+        .value_parser(value_parser!(f32))
+        .value_name("FACTOR")
+        .requires(A_L_BEST_FIT)
+        .action(ArgAction::Set)
+        .global(true)
+}
+
+fn arg_no_fallback() -> Arg {
+    Arg::new(A_L_NO_FALLBACK)
+        .help("Do not fall back to the default standard if the best fit is not convincing")
+        .long_help(formatcp!(
+            "Do not fall back to the default standard \
+if the best fit is ambiguous or not viable; \
+instead use the best fit if it is ambiguous, \
+and fail if it is not viable. \
+Only used with --{A_L_BEST_FIT}.",
+        ))
+        .long(A_L_NO_FALLBACK)
+        .alias("no-fallback")
+        .requires(A_L_BEST_FIT)
+        .action(ArgAction::SetTrue)
+        .global(true)
+}
+
 fn arg_all() -> Arg {
     Arg::new(A_L_ALL)
         .help("Check coverage/do mapping versus all OSH directory standards")
@@ -188,8 +848,135 @@ relative to the project root, like all paths handled by this tool. \
         .global(true)
 }
 
+fn arg_tags() -> Arg {
+    Arg::new(A_L_TAGS)
+        .help("Restrict the per-tag scores and the checks to records with any of these tags")
+        .long_help(formatcp!(
+            "Restrict the per-tag scores (in the JSON output of '{SC_N_MAP}') \
+and the checks (of '{SC_N_ANNOTATE}', '{SC_N_HOOK}' and '{SC_N_CHECK}') \
+to the records with any of these tags (e.g. 'doc,source'). \
+Paths that are out of the standard belong to no tag, \
+so they are not checked when this is given, \
+except by '{SC_N_CHECK}', which uses the tags of the record \
+it suggests for such a path."
+        ))
+        .long(A_L_TAGS)
+        .num_args(1)
+        .value_delimiter(',')
+        .value_name("TAG")
+        .action(ArgAction::Append)
+        .global(true)
+}
+
+fn arg_rule() -> Arg {
+    Arg::new(A_L_RULE)
+        .help("Also check these built-in house rules")
+        .long_help(formatcp!(
+            "Also check these built-in house rules, \
+with their default settings; \
+the findings are reported by '{SC_N_CHECK}' and '{SC_N_ANNOTATE}'. \
+Known rules: \
+'module-readme' - every module has a 'README.md'; \
+'root-files' - only whitelisted files (README, LICENSE, okh.toml, ...) \
+directly in the project root."
+        ))
+        .long(A_L_RULE)
+        .num_args(1)
+        .value_delimiter(',')
+        .value_parser(PossibleValuesParser::new(
+            rules::Registry::BUILTIN.iter().copied(),
+        ))
+        .value_name("RULE")
+        .action(ArgAction::Append)
+        .global(true)
+}
+
+fn arg_rules_file() -> Arg {
+    Arg::new(A_L_RULES_FILE)
+        .help("Also check the house rules configured in this TOML file")
+        .long_help(
+            "Also check the house rules configured in this TOML file; \
+it may configure the built-in rules (in the tables '[module-readme]' and '[root-files]'), \
+and define additional ones, forbidding paths matching a regex ('[[forbid]]'). \
+See the README for an example.",
+        )
+        .long(A_L_RULES_FILE)
+        .num_args(1)
+        .value_parser(value_parser!(std::path::PathBuf))
+        .value_name("TOML-FILE")
+        .value_hint(ValueHint::FilePath)
+        .action(ArgAction::Set)
+        .global(true)
+}
+
+fn examples() -> String {
+    format!(
+        r#"Examples:
+  $ # 1. Lists git tracked files,
+  $ #    and rates them with all the known standards:
+  $ git ls-files --recurse-submodules \
+    | sed -e 's/^"\(.*\)"$/\1/' \
+    | {} rate
+
+  $ # 2. Lists git tracked files,
+  $ #    and maps them to the default standard:
+  $ git ls-files --recurse-submodules \
+    | sed -e 's/^"\(.*\)"$/\1/' \
+    | {} map
+
+  $ # 3. Same as 1., but supports any characters in paths,
+  $ #    including new-lines:
+  $ git ls-files -z --recurse-submodules \
+    | {} --null rate
+
+  $ # 4. Shows how the rating with the default standard
+  $ #    changed between the main branch and the current revision:
+  $ {} diff --old-rev origin/main --new-rev HEAD --format text
+
+  $ # 5. Checks a (new or modified) standard definition for problems:
+  $ {} lint-std --std-csv mod/unixish/definition.csv
+
+  $ # 6. Exports the default standard as YAML:
+  $ {} show --format yaml
+
+  $ # 7. Checks whether the project still fits the standard
+  $ #    declared in its okh.toml best:
+  $ git ls-files | {} drift
+
+  $ # 8. Rejects commits that add paths that are out of the standard:
+  $ {} install-hook
+
+  $ # 9. Checks only the paths added in the current branch:
+  $ {} check --changed-since origin/main
+
+  $ # 10. Annotates only deviations concerning documentation and sources:
+  $ git ls-files | {} --tags doc,source annotate --format github
+
+  $ # 11. Also checks house rules, and outputs SARIF for code scanning:
+  $ git ls-files | {} --rule module-readme --rules-file rules.toml annotate --format sarif
+
+  $ # 12. Browses the coverage interactively,
+  $ #     comparing the standards (requires the 'tui' feature):
+  $ git ls-files | {} --best-fit tui
+"#,
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+        clap::crate_name!(),
+    )
+}
+
 pub fn arg_matcher() -> Command {
-    command!()
+    #[allow(unused_mut)]
+    let mut command = command!()
         .help_expected(true)
         .propagate_version(true)
         .subcommand_negates_reqs(true)
@@ -200,30 +987,25 @@ pub fn arg_matcher() -> Command {
             "Please leave feedback of any kind here (including bug reports):\n<{PROJECT_ISSUES_URL}>"
         ))
         .after_help("Please use --help for Examples.")
-        .after_long_help(format!(
-            r#"Examples:
-  $ # 1. Lists git tracked files,
-  $ #    and rates them with all the known standards:
-  $ git ls-files --recurse-submodules \
-        | sed -e 's/^"\(.*\)"$/\1/' \
-        | {} rate
-
-  $ # 2. Lists git tracked files,
-  $ #    and maps them to the default standard:
-  $ git ls-files --recurse-submodules \
-        | sed -e 's/^"\(.*\)"$/\1/' \
-        | {} map
-"#,
-            clap::crate_name!(),
-            clap::crate_name!(),
-        ))
+        .after_long_help(examples())
         .arg(arg_output().index(1))
         .arg(arg_version())
         .arg(arg_quiet())
         .arg(arg_input_listing())
+        .arg(arg_listing_format())
+        .arg(arg_null())
         .arg(arg_ignore_paths())
+        .arg(arg_tags())
+        .arg(arg_rule())
+        .arg(arg_rules_file())
+        .arg(arg_case_insensitive())
+        .arg(arg_nfc())
+        .arg(arg_normalize_separators())
         .arg(arg_standard())
         .arg(arg_best_fit())
+        .arg(arg_min_fit())
+        .arg(arg_min_margin())
+        .arg(arg_no_fallback())
         .arg(arg_all())
         .group(
             ArgGroup::new("grp_standard")
@@ -232,4 +1014,22 @@ pub fn arg_matcher() -> Command {
         )
         .subcommand(subcom_rate())
         .subcommand(subcom_map())
+        .subcommand(subcom_diff())
+        .subcommand(subcom_lint_std())
+        .subcommand(subcom_show())
+        .subcommand(subcom_drift())
+        .subcommand(subcom_hook())
+        .subcommand(subcom_check())
+        .subcommand(subcom_annotate())
+        .subcommand(subcom_badge())
+        .subcommand(subcom_install_hook());
+    #[cfg(feature = "serve")]
+    {
+        command = command.subcommand(subcom_serve());
+    }
+    #[cfg(feature = "tui")]
+    {
+        command = command.subcommand(subcom_tui());
+    }
+    command
 }
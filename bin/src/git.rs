@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{io, path::PathBuf, process::Command};
+
+/// Runs git with the given arguments in the current working directory,
+/// and returns its standard output.
+fn run(args: &[&str]) -> io::Result<Vec<u8>> {
+    log::debug!("Running: git {}", args.join(" "));
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Splits NUL-separated git output into paths.
+fn split_nul(output: &[u8]) -> impl Iterator<Item = PathBuf> + '_ {
+    output
+        .split(|byte| *byte == b'\0')
+        .filter(|part| !part.is_empty())
+        .map(|part| PathBuf::from(String::from_utf8_lossy(part).as_ref()))
+}
+
+/// Lists all files tracked by git in the given revision,
+/// relative to the repository root.
+///
+/// # Errors
+///
+/// If git could not be run, or the revision is unknown.
+pub fn ls_tree(rev: &str) -> io::Result<Vec<PathBuf>> {
+    let output = run(&["ls-tree", "-r", "-z", "--full-tree", "--name-only", rev])?;
+    Ok(split_nul(&output).collect())
+}
+
+/// Whether the repository has a `HEAD` commit yet.
+pub fn has_head() -> bool {
+    run(&["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok()
+}
+
+/// Lists the paths that are added or renamed in the index (staging area),
+/// compared to `HEAD`.
+///
+/// # Errors
+///
+/// If git could not be run.
+pub fn staged_additions() -> io::Result<Vec<PathBuf>> {
+    let output = run(&[
+        "diff",
+        "--cached",
+        "--name-only",
+        "-z",
+        "--no-renames",
+        "--diff-filter=A",
+    ])?;
+    Ok(split_nul(&output).collect())
+}
+
+/// Returns the path of the hooks directory of the current repository.
+///
+/// # Errors
+///
+/// If git could not be run, or we are not within a git repository.
+pub fn hooks_dir() -> io::Result<PathBuf> {
+    let output = run(&["rev-parse", "--git-path", "hooks"])?;
+    Ok(PathBuf::from(String::from_utf8_lossy(&output).trim()))
+}
+
+/// Lists the paths added, copied or renamed in `HEAD`,
+/// compared to where it branched off from `rev`.
+///
+/// # Errors
+///
+/// If git could not be run, or the revision is unknown.
+pub fn added_since(rev: &str) -> io::Result<Vec<PathBuf>> {
+    let range = format!("{rev}...HEAD");
+    let output = run(&["diff", "--name-only", "-z", "--diff-filter=ACR", &range])?;
+    Ok(split_nul(&output).collect())
+}
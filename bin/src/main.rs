@@ -3,28 +3,42 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 mod cli;
+mod git;
+mod output;
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(feature = "tui")]
+mod tui;
 
 use std::{
-    collections::HashSet,
-    env,
+    collections::{BTreeMap, HashSet},
+    env, fs,
     io::{self, BufRead, Write},
     path::{Path, PathBuf},
     rc::Rc,
-    str::FromStr,
 };
 
 use clap::ArgMatches;
 use cli::{A_L_INPUT_LISTING, A_L_QUIET, A_L_VERSION};
 use osh_dir_std::{
-    constants, cover_listing_by_stds,
-    format::{Rec, Record},
-    rate_listing_by_stds,
-    stds::Standards,
-    Coverage, RatingCont,
+    additions,
+    badge::{self, BadgeThresholds},
+    check_drift, ci, constants, cover_listing_by_stds, cover_listing_with, drift,
+    format::{DirStandard, DirStd},
+    graph::{self, GraphFormat},
+    interchange::StdFormat,
+    lint,
+    listing::{self, ListingFormat},
+    matching::MatchPolicy,
+    rate_listing, rate_listing_by_stds,
+    rules::{self, RulesConfig},
+    stds::{self, Standards},
+    stream_listing_by_stds, tags, BestFitThresholds, Coverage, CoverageDiff, CoverageStats,
+    ListingSource, Ranking, Rating, RatingCont, Report,
 };
+use output::MappingWriter;
 use regex::Regex;
 use serde::Serialize;
-use std::sync::LazyLock;
 use tracing::{error, metadata::LevelFilter};
 use tracing_subscriber::{
     fmt,
@@ -33,8 +47,6 @@ use tracing_subscriber::{
     Registry,
 };
 
-pub static EMPTY_PATH: LazyLock<PathBuf> = LazyLock::new(PathBuf::new);
-
 fn ignored_paths(args: &ArgMatches) -> Regex {
     let ignored_paths = args
         .get_one::<Regex>(cli::A_L_IGNORE_PATHS)
@@ -44,6 +56,40 @@ fn ignored_paths(args: &ArgMatches) -> Regex {
     ignored_paths
 }
 
+fn tags_filter(args: &ArgMatches) -> Option<HashSet<String>> {
+    let tags = args
+        .get_many::<String>(cli::A_L_TAGS)?
+        .cloned()
+        .collect::<HashSet<_>>();
+    let known_tags = lint::known_tags();
+    for tag in &tags {
+        if !known_tags.contains(tag.as_str()) {
+            log::warn!("No known standard has records with the tag '{tag}'");
+        }
+    }
+    Some(tags)
+}
+
+fn house_rules(args: &ArgMatches) -> anyhow::Result<rules::Registry> {
+    let mut registry = rules::Registry::new();
+    for rule_name in args.get_many::<String>(cli::A_L_RULE).into_iter().flatten() {
+        registry.add_builtin(rule_name)?;
+    }
+    if let Some(rules_file) = args.get_one::<PathBuf>(cli::A_L_RULES_FILE) {
+        log::info!("Reading house rules from '{}' ...", rules_file.display());
+        registry.add_config(&RulesConfig::from_file(rules_file)?)?;
+    }
+    Ok(registry)
+}
+
+fn match_policy(args: &ArgMatches) -> MatchPolicy {
+    MatchPolicy {
+        case_insensitive: args.get_flag(cli::A_L_CASE_INSENSITIVE),
+        unicode_nfc: args.get_flag(cli::A_L_NFC),
+        normalize_separators: args.get_flag(cli::A_L_NORMALIZE_SEPARATORS),
+    }
+}
+
 fn input_stream(args: &ArgMatches) -> io::Result<Box<dyn BufRead>> {
     let input_listing = args.get_one::<PathBuf>(A_L_INPUT_LISTING);
     log::info!(
@@ -53,34 +99,131 @@ fn input_stream(args: &ArgMatches) -> io::Result<Box<dyn BufRead>> {
     cli_utils::create_input_reader(input_listing)
 }
 
+fn listing_format(args: &ArgMatches) -> ListingFormat {
+    if args.get_flag(cli::A_L_NULL) {
+        ListingFormat::Null
+    } else {
+        args.get_one::<ListingFormat>(cli::A_L_LISTING_FORMAT)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
 fn dirs_and_files(
     listing_strm: &mut Box<dyn BufRead>,
+    listing_format: ListingFormat,
+    streaming: bool,
 ) -> impl Iterator<Item = io::Result<Rc<PathBuf>>> + '_ {
-    let lines_iter = cli_utils::lines_iterator(listing_strm, true);
-    let no_comments_lines = lines_iter.filter(|line_res| {
-        line_res
-            .as_ref()
-            .map_or(true, |line| !(line.starts_with('#') || line.is_empty()))
-    });
-    let files = no_comments_lines.fuse().map(line_to_path_res);
+    listing_format
+        .source(listing_strm)
+        .dirs_and_files(streaming)
+}
+
+/// Reads a complete listing, either from a git revision or a listing file,
+/// including the ancestor dirs of all the listed paths.
+fn read_listing(
+    sub_com_args: &ArgMatches,
+    arg_rev: &str,
+    arg_listing: &str,
+    listing_format: ListingFormat,
+) -> io::Result<Vec<Rc<PathBuf>>> {
+    if let Some(rev) = sub_com_args.get_one::<String>(arg_rev) {
+        log::info!("Reading listing from git revision '{rev}' ...");
+        listing::from_paths(git::ls_tree(rev)?)
+            .dirs_and_files(false)
+            .collect()
+    } else {
+        read_listing_file(sub_com_args.get_one::<PathBuf>(arg_listing), listing_format)
+    }
+}
 
-    // In case the input-listing only contains files,
-    // we also want to iterate over their ancestor dirs,
-    // while avoiding duplicate visiting of those.
-    // As a side-effect, this also filters out duplicate input of any kind,
-    // file or directory.
-    // However, this also creates a cache in memory,
-    // that in the end will usually be as big as the whole input-listing its self.
-    // TODO Thus we might want to add an option to skip this filtering, in case of large input listings.
-    let mut dirs_adder = DirsAdder::new();
-    files.flat_map(move |path_res| dirs_adder.call_mut(path_res))
+/// Reads a complete listing from a file, or from stdin if none is given,
+/// including the ancestor dirs of all the listed paths.
+fn read_listing_file(
+    listing_file: Option<&PathBuf>,
+    listing_format: ListingFormat,
+) -> io::Result<Vec<Rc<PathBuf>>> {
+    log::info!(
+        "Reading listing from {} ...",
+        cli_utils::create_input_reader_description(listing_file)
+    );
+    let mut listing_strm = cli_utils::create_input_reader(listing_file)?;
+    dirs_and_files(&mut listing_strm, listing_format, false).collect()
+}
+
+/// Diffs the coverages of the old and the new listing,
+/// for each of the selected standards.
+/// In case of [`Standards::BestFit`],
+/// the standard that fits the new listing best is used for both.
+fn diff_by_stds(
+    old: &[Rc<PathBuf>],
+    new: &[Rc<PathBuf>],
+    ignored_paths: &Regex,
+    policy: MatchPolicy,
+    stds: &Standards,
+) -> anyhow::Result<Vec<CoverageDiff>> {
+    let diff_stds = if matches!(stds, Standards::BestFit(_)) {
+        let best = rate_listing_by_stds(new.iter().cloned().map(Ok), ignored_paths, policy, stds)?;
+        best.into_iter()
+            .next()
+            .map_or(Standards::Default, |rating_cont| {
+                Standards::Specific(rating_cont.rating.name)
+            })
+    } else {
+        stds.clone()
+    };
+    let old_covs = cover_listing_by_stds(
+        old.iter().cloned().map(Ok),
+        ignored_paths,
+        policy,
+        &diff_stds,
+    )?;
+    let new_covs = cover_listing_by_stds(
+        new.iter().cloned().map(Ok),
+        ignored_paths,
+        policy,
+        &diff_stds,
+    )?;
+    let mut diffs = vec![];
+    for new_cov in &new_covs {
+        if let Some(old_cov) = old_covs
+            .iter()
+            .find(|old_cov| old_cov.std.name == new_cov.std.name)
+        {
+            diffs.push(osh_dir_std::diff(old_cov, new_cov)?);
+        }
+    }
+    diffs.sort_by_key(|cov_diff| cov_diff.std.name);
+    Ok(diffs)
+}
+
+fn rating_from_stats(stats: &CoverageStats) -> RatingCont {
+    RatingCont {
+        rating: Rating {
+            name: stats.std.name.to_owned(),
+            factor: stats.rate(),
+        },
+        coverage: None,
+    }
 }
 
 fn standards(args: &ArgMatches) -> Standards {
     let all = args.get_flag(cli::A_L_ALL);
     let best_fit = args.get_flag(cli::A_L_BEST_FIT);
     let std = args.get_one::<String>(cli::A_L_STANDARD);
-    Standards::from_opts(all, best_fit, std)
+    let defaults = BestFitThresholds::default();
+    let thresholds = BestFitThresholds {
+        min_factor: args
+            .get_one::<f32>(cli::A_L_MIN_FIT)
+            .copied()
+            .unwrap_or(defaults.min_factor),
+        min_margin: args
+            .get_one::<f32>(cli::A_L_MIN_MARGIN)
+            .copied()
+            .unwrap_or(defaults.min_margin),
+        fallback_to_default: !args.get_flag(cli::A_L_NO_FALLBACK),
+    };
+    Standards::from_opts(all, best_fit, std).with_thresholds(thresholds)
 }
 
 fn out_stream(args: &ArgMatches) -> io::Result<Box<dyn Write>> {
@@ -101,51 +244,6 @@ fn print_version_and_exit(quiet: bool) {
     std::process::exit(0);
 }
 
-/// Removes "./" or ".\" (<- Windows) from the beginning of paths.
-fn line_to_path_res(res_line: io::Result<String>) -> io::Result<PathBuf> {
-    res_line.map(|mut line| {
-        if line.starts_with("./") || line.starts_with(".\\") {
-            line.pop();
-            line.pop();
-        }
-        PathBuf::from_str(&line)
-            .expect("As the error type here is core::convert::Infallible, this can never happen")
-    })
-}
-
-struct DirsAdder {
-    visited_dirs_cache: HashSet<Rc<PathBuf>>,
-}
-
-impl DirsAdder {
-    pub fn new() -> Self {
-        Self {
-            visited_dirs_cache: HashSet::new(),
-        }
-    }
-
-    pub fn call_mut<P: AsRef<Path>>(
-        &mut self,
-        path_res: io::Result<P>,
-    ) -> Vec<io::Result<Rc<PathBuf>>> {
-        #[allow(clippy::option_if_let_else)]
-        if let Ok(path) = path_res {
-            path.as_ref()
-                .ancestors()
-                .filter(|ancestor| ancestor != &EMPTY_PATH.as_path())
-                .map(Path::to_path_buf)
-                .map(Rc::new) // We do this to not duplicate memory in cache and the iterator and the coverages
-                .filter(|ancestor| self.visited_dirs_cache.insert(Rc::clone(ancestor)))
-                .map(Ok)
-                .collect::<Vec<io::Result<_>>>()
-        } else {
-            vec![path_res
-                .map(|path| Path::to_path_buf(path.as_ref()))
-                .map(Rc::new)]
-        }
-    }
-}
-
 /// Sets up logging, with a way to change the log level later on,
 /// and with all output going to stderr,
 /// as suggested by <https://clig.dev/>.
@@ -170,27 +268,643 @@ fn setup_logging() -> anyhow::Result<Handle<LevelFilter, Registry>> {
     Ok(reload_handle)
 }
 
+/// The options shared by all sub-commands.
+struct Context<'a> {
+    args: &'a ArgMatches,
+    ignored_paths: Regex,
+    policy: MatchPolicy,
+    listing_format: ListingFormat,
+    stds: Standards,
+    /// Only records with any of these tags are considered
+    /// in the per-tag scores and the checks.
+    tags: Option<HashSet<String>>,
+    /// The house rules to check in addition to the standard
+    rules: rules::Registry,
+    pretty: bool,
+}
+
+impl Context<'_> {
+    fn dirs_and_files<'s>(
+        &self,
+        listing_strm: &'s mut Box<dyn BufRead>,
+        streaming: bool,
+    ) -> impl Iterator<Item = io::Result<Rc<PathBuf>>> + 's {
+        dirs_and_files(listing_strm, self.listing_format, streaming)
+    }
+
+    fn write_json<T: Serialize>(
+        &self,
+        out_stream: &mut dyn Write,
+        value: &T,
+    ) -> anyhow::Result<()> {
+        log::info!("Converting results to JSON ...");
+        let json = if self.pretty {
+            serde_json::to_string_pretty(value)
+        } else {
+            serde_json::to_string(value)
+        }?;
+        out_stream.write_all(json.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Rates the listing with all standards,
+/// and writes them out ranked, best fit first.
+fn rank(
+    ctx: &Context,
+    thresholds: &BestFitThresholds,
+    streaming: bool,
+    include_coverage: bool,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut listing_strm = input_stream(ctx.args)?;
+    let dirs_and_files = ctx.dirs_and_files(&mut listing_strm, streaming);
+
+    log::info!("Ranking standards by how well they fit the listing ...");
+    let ratings = if streaming {
+        stream_listing_by_stds(
+            dirs_and_files,
+            &ctx.ignored_paths,
+            ctx.policy,
+            &Standards::All,
+            |_| Ok(()),
+        )?
+        .iter()
+        .map(rating_from_stats)
+        .collect()
+    } else {
+        rate_listing(dirs_and_files, &ctx.ignored_paths, ctx.policy)?
+    };
+    let mut ranking = Ranking::new(ratings, thresholds);
+    log::info!(
+        "Best fit verdict: {:?}, chosen: {:?}",
+        ranking.verdict,
+        ranking.chosen
+    );
+    if !include_coverage {
+        ranking = ranking.remove_coverages();
+    }
+
+    ctx.write_json(out_stream, &ranking)
+}
+
+fn rate(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let streaming = sub_com_args.get_flag(cli::A_L_STREAM);
+    let include_coverage = sub_com_args.get_flag(cli::A_L_INCLUDE_COVERAGE);
+    if let Standards::BestFit(thresholds) = &ctx.stds {
+        return rank(ctx, thresholds, streaming, include_coverage, out_stream);
+    }
+    let mut listing_strm = input_stream(ctx.args)?;
+    let dirs_and_files = ctx.dirs_and_files(&mut listing_strm, streaming);
+
+    log::info!("Rating listing according to standard(s) ...");
+    let mut rating = if streaming {
+        stream_listing_by_stds(
+            dirs_and_files,
+            &ctx.ignored_paths,
+            ctx.policy,
+            &ctx.stds,
+            |_| Ok(()),
+        )?
+        .iter()
+        .map(rating_from_stats)
+        .collect()
+    } else {
+        rate_listing_by_stds(dirs_and_files, &ctx.ignored_paths, ctx.policy, &ctx.stds)?
+    };
+    if !include_coverage {
+        rating = rating
+            .into_iter()
+            .map(RatingCont::remove_coverage)
+            .collect();
+    }
+
+    ctx.write_json(out_stream, &rating)
+}
+
+fn map_streaming(ctx: &Context, format: &str, out_stream: &mut dyn Write) -> anyhow::Result<()> {
+    if matches!(ctx.stds, Standards::BestFit(_)) {
+        anyhow::bail!(
+            "Mapping in streaming mode is not supported with --{}",
+            cli::A_L_BEST_FIT
+        );
+    }
+    let mut listing_strm = input_stream(ctx.args)?;
+    let dirs_and_files = ctx.dirs_and_files(&mut listing_strm, true);
+
+    log::info!("Mapping listing to standard(s), streaming ...");
+    let mut mapping_writer = MappingWriter::new(format, out_stream);
+    let stats = stream_listing_by_stds(
+        dirs_and_files,
+        &ctx.ignored_paths,
+        ctx.policy,
+        &ctx.stds,
+        |mapping| mapping_writer.write(mapping),
+    )?;
+    mapping_writer.finish()?;
+    for std_stats in &stats {
+        log::info!("Rating for '{}': {}", std_stats.std.name, std_stats.rate());
+    }
+    Ok(())
+}
+
+fn map(ctx: &Context, sub_com_args: &ArgMatches, out_stream: &mut dyn Write) -> anyhow::Result<()> {
+    let format = sub_com_args
+        .get_one::<String>(cli::A_L_FORMAT)
+        .map_or(cli::FORMAT_JSON, String::as_str);
+    let graph_format = format.parse::<GraphFormat>().ok();
+    if sub_com_args.get_flag(cli::A_L_STREAM) {
+        if graph_format.is_some() {
+            anyhow::bail!(
+                "The graph formats need the whole listing, and thus do not support --{}",
+                cli::A_L_STREAM
+            );
+        }
+        return map_streaming(ctx, format, out_stream);
+    }
+    let mut listing_strm = input_stream(ctx.args)?;
+    let dirs_and_files = ctx.dirs_and_files(&mut listing_strm, false);
+
+    log::info!("Mapping listing to standard(s) ...");
+    let coverage =
+        cover_listing_by_stds(dirs_and_files, &ctx.ignored_paths, ctx.policy, &ctx.stds)?;
+
+    if format == cli::FORMAT_JSON {
+        let mut report = Report::new(&coverage);
+        if let Some(tags) = &ctx.tags {
+            report.retain_tags(tags);
+        }
+        ctx.write_json(out_stream, &report)
+    } else if let Some(graph_fmt) = graph_format {
+        for std_coverage in &coverage {
+            graph::write_mapping_graph(std_coverage, graph_fmt, &mut *out_stream)?;
+        }
+        Ok(())
+    } else {
+        let mut mapping_writer = MappingWriter::new(format, out_stream);
+        for std_coverage in &coverage {
+            for mapping in std_coverage.mappings() {
+                mapping_writer.write(&mapping)?;
+            }
+        }
+        Ok(mapping_writer.finish()?)
+    }
+}
+
+fn diff(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let old = read_listing(
+        sub_com_args,
+        cli::A_L_OLD_REV,
+        cli::A_L_OLD_LISTING,
+        ctx.listing_format,
+    )?;
+    let new = read_listing(
+        sub_com_args,
+        cli::A_L_NEW_REV,
+        cli::A_L_NEW_LISTING,
+        ctx.listing_format,
+    )?;
+
+    log::info!("Diffing coverages of the two listings ...");
+    let diffs = diff_by_stds(&old, &new, &ctx.ignored_paths, ctx.policy, &ctx.stds)?;
+
+    let format = sub_com_args.get_one::<String>(cli::A_L_FORMAT);
+    if format.map(String::as_str) == Some(cli::FORMAT_TEXT) {
+        for cov_diff in &diffs {
+            write!(out_stream, "{cov_diff}")?;
+        }
+        Ok(())
+    } else {
+        ctx.write_json(out_stream, &diffs)
+    }
+}
+
+/// Returns whether any problems were found.
+fn lint_std(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<bool> {
+    let known_tags = lint::known_tags();
+    let format = sub_com_args.get_one::<String>(cli::A_L_FORMAT);
+    let text = format.map(String::as_str) == Some(cli::FORMAT_TEXT);
+    let mut all_findings = BTreeMap::new();
+    for csv_file in sub_com_args
+        .get_many::<PathBuf>(cli::A_L_STD_CSV)
+        .into_iter()
+        .flatten()
+    {
+        log::info!("Linting standard definition '{}' ...", csv_file.display());
+        let findings = lint::lint_csv_file(csv_file, &known_tags)?;
+        if text {
+            for finding in &findings {
+                writeln!(out_stream, "{}:{finding}", csv_file.display())?;
+            }
+        }
+        all_findings.insert(csv_file.display().to_string(), findings);
+    }
+    if !text {
+        ctx.write_json(out_stream, &all_findings)?;
+    }
+    Ok(all_findings.values().any(|findings| !findings.is_empty()))
+}
+
+/// Returns whether the project drifted away from its declared standard.
+fn drift(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<bool> {
+    let declared = if let Some(std_name) = ctx.args.get_one::<String>(cli::A_L_STANDARD) {
+        std_name.clone()
+    } else {
+        let declared_in = sub_com_args
+            .get_one::<PathBuf>(cli::A_L_DECLARED_IN)
+            .expect("Has a default value");
+        log::info!(
+            "Reading declared standard from '{}' ...",
+            declared_in.display()
+        );
+        drift::read_declared_std(declared_in)?
+    };
+    let min_margin = sub_com_args
+        .get_one::<f32>(cli::A_L_MARGIN)
+        .copied()
+        .expect("Has a default value");
+    let mut listing_strm = input_stream(ctx.args)?;
+    let dirs_and_files = ctx.dirs_and_files(&mut listing_strm, false);
+
+    log::info!("Checking for drift from the declared standard '{declared}' ...");
+    let drift = check_drift(
+        dirs_and_files,
+        &ctx.ignored_paths,
+        ctx.policy,
+        &declared,
+        min_margin,
+    )?;
+
+    let format = sub_com_args.get_one::<String>(cli::A_L_FORMAT);
+    if format.map(String::as_str) == Some(cli::FORMAT_TEXT) {
+        write!(out_stream, "{drift}")?;
+    } else {
+        ctx.write_json(out_stream, &drift)?;
+    }
+    Ok(drift.drifted)
+}
+
+/// Returns the standard of the project:
+/// the one given on the command line,
+/// or else the one declared in okh.toml,
+/// or else the default one.
+fn project_std(ctx: &Context) -> anyhow::Result<&'static DirStd> {
+    let okh = Path::new("okh.toml");
+    let std_name = match &ctx.stds {
+        Standards::Specific(std_name) => std_name.clone(),
+        Standards::Default if okh.exists() => drift::read_declared_std(okh)?,
+        Standards::Default => osh_dir_std::DEFAULT_STD_NAME.to_owned(),
+        Standards::All | Standards::BestFit(_) => anyhow::bail!(
+            "Checking staged paths requires a single standard; \
+please use --{} or none",
+            cli::A_L_STANDARD
+        ),
+    };
+    Ok(stds::by_name(&std_name)?)
+}
+
+/// Whether the record of the standard with the given path
+/// has any of the tags in the filter (if there is one).
+/// Without a record, a path belongs to no tag.
+fn in_tags_filter(ctx: &Context, std: &DirStd, rec_path: Option<&str>) -> bool {
+    let Some(filter) = &ctx.tags else {
+        return true;
+    };
+    rec_path
+        .and_then(|path| std.records.iter().find(|rec| rec.path == path))
+        .is_some_and(|rec| tags::any_in_filter(tags::record_tags(rec), filter))
+}
+
+/// Returns whether any of the staged paths violate the standard.
+fn hook(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<bool> {
+    let std = project_std(ctx)?;
+    let existing = if git::has_head() {
+        listing::from_paths(git::ls_tree("HEAD")?)
+            .dirs_and_files(false)
+            .map(|path_res| path_res.map(|path| path.as_ref().clone()))
+            .collect::<io::Result<HashSet<_>>>()?
+    } else {
+        HashSet::new()
+    };
+    let added = git::staged_additions()?;
+    log::info!(
+        "Checking {} staged path(s) against standard '{}' ...",
+        added.len(),
+        std.name
+    );
+    let mut violations =
+        additions::check_additions(std, &ctx.ignored_paths, ctx.policy, &existing, added)?;
+    violations.retain(|violation| {
+        let record = match &violation.problem {
+            additions::Problem::Untracked { record } => Some(record.as_str()),
+            additions::Problem::OutOfStandard => None,
+        };
+        in_tags_filter(ctx, std, record)
+    });
+
+    let format = sub_com_args.get_one::<String>(cli::A_L_FORMAT);
+    if format.map(String::as_str) == Some(cli::FORMAT_TEXT) {
+        for violation in &violations {
+            writeln!(out_stream, "{violation}")?;
+        }
+    } else {
+        ctx.write_json(out_stream, &violations)?;
+    }
+    Ok(!violations.is_empty())
+}
+
+/// The JSON output of [`check`], if house rules are checked.
 #[derive(Serialize)]
-struct CovEntry {
-    name: String,
-    coverage: Coverage,
-    records: Vec<Record>,
-}
-
-impl From<Coverage> for CovEntry {
-    fn from(coverage: Coverage) -> Self {
-        let records = coverage
-            .r#in
-            .keys()
-            .map(ToOwned::to_owned)
-            .map(Rec::to_record)
-            .collect::<Vec<_>>();
-        Self {
-            name: coverage.std.name.to_owned(),
-            coverage,
-            records,
+struct CheckOutput<'a> {
+    misfits: &'a [additions::Misfit],
+    findings: &'a [rules::Finding],
+}
+
+/// Returns whether any of the added paths do not fit the standard,
+/// or violate a house rule.
+fn check(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<bool> {
+    let std = project_std(ctx)?;
+    let added = if let Some(rev) = sub_com_args.get_one::<String>(cli::A_L_CHANGED_SINCE) {
+        log::info!("Reading paths added since git revision '{rev}' ...");
+        git::added_since(rev)?
+    } else if ctx.listing_format == ListingFormat::Null {
+        additions::parse_name_status_nul(input_stream(ctx.args)?)?
+    } else {
+        additions::parse_name_status(input_stream(ctx.args)?)?
+    }
+    .into_iter()
+    .collect::<HashSet<_>>();
+    log::info!(
+        "Checking {} added path(s) against standard '{}' ...",
+        added.len(),
+        std.name
+    );
+    let project_listing =
+        if let Some(listing_file) = sub_com_args.get_one::<PathBuf>(cli::A_L_PROJECT_LISTING) {
+            read_listing_file(Some(listing_file), ctx.listing_format)?
+        } else {
+            listing::from_paths(git::ls_tree("HEAD")?)
+                .dirs_and_files(false)
+                .collect::<io::Result<Vec<_>>>()?
+        };
+    let mut misfits = additions::check_changes(
+        std,
+        &ctx.ignored_paths,
+        ctx.policy,
+        project_listing.iter().cloned().map(Ok::<_, io::Error>),
+        &added,
+    )?;
+    misfits.retain(|misfit| in_tags_filter(ctx, std, misfit.suggestion.as_deref()));
+    let findings = if ctx.rules.is_empty() {
+        vec![]
+    } else {
+        let coverage = cover_listing_with(
+            project_listing.iter().cloned().map(Ok::<_, io::Error>),
+            &ctx.ignored_paths,
+            ctx.policy,
+            std,
+        )?;
+        // Only report what concerns the added paths
+        ctx.rules
+            .check(&project_listing, &coverage)
+            .into_iter()
+            .filter(|finding| added.iter().any(|path| path.starts_with(&finding.path)))
+            .collect()
+    };
+
+    let format = sub_com_args.get_one::<String>(cli::A_L_FORMAT);
+    if format.map(String::as_str) == Some(cli::FORMAT_TEXT) {
+        for misfit in &misfits {
+            writeln!(out_stream, "{misfit}")?;
+        }
+        for finding in &findings {
+            writeln!(out_stream, "{finding}")?;
         }
+    } else if ctx.rules.is_empty() {
+        ctx.write_json(out_stream, &misfits)?;
+    } else {
+        ctx.write_json(
+            out_stream,
+            &CheckOutput {
+                misfits: &misfits,
+                findings: &findings,
+            },
+        )?;
     }
+    Ok(!misfits.is_empty() || !findings.is_empty())
+}
+
+fn annotate(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let mut listing_strm = input_stream(ctx.args)?;
+    let dirs_and_files = ctx
+        .dirs_and_files(&mut listing_strm, false)
+        .collect::<io::Result<Vec<_>>>()?;
+
+    log::info!("Checking listing against standard(s) ...");
+    let coverages = cover_listing_by_stds(
+        dirs_and_files.iter().cloned().map(Ok),
+        &ctx.ignored_paths,
+        ctx.policy,
+        &ctx.stds,
+    )?;
+    let mut checks = coverages.iter().flat_map(ci::checks).collect::<Vec<_>>();
+    if let Some(tags) = &ctx.tags {
+        checks.retain(|check| tags::any_in_filter(check.tags.iter().copied(), tags));
+    }
+    for coverage in &coverages {
+        checks.extend(ci::rule_checks(
+            coverage.std.name,
+            ctx.rules.check(&dirs_and_files, coverage),
+        ));
+    }
+
+    let format = sub_com_args
+        .get_one::<String>(cli::A_L_FORMAT)
+        .map_or(cli::FORMAT_JUNIT, String::as_str);
+    match format {
+        cli::FORMAT_GITLAB => ci::write_gitlab(&checks, out_stream)?,
+        cli::FORMAT_GITHUB => ci::write_github(&checks, out_stream)?,
+        cli::FORMAT_SARIF => ci::write_sarif(&checks, out_stream)?,
+        cli::FORMAT_TEXT => ci::write_text(&checks, out_stream)?,
+        _ => ci::write_junit(&checks, out_stream)?,
+    }
+    Ok(())
+}
+
+fn write_module_badges(
+    coverage: &Coverage,
+    prefix: &Path,
+    modules_dir: &Path,
+    label: &str,
+    thresholds: BadgeThresholds,
+) -> io::Result<()> {
+    for (mod_path, mod_coverage) in &coverage.modules {
+        let full_mod_path = prefix.join(mod_path);
+        let rating = Rating {
+            name: mod_coverage.std.name.to_owned(),
+            factor: mod_coverage.rate(),
+        };
+        let mod_label = format!("{label} {}", full_mod_path.display());
+        let file_name = format!(
+            "{}.svg",
+            full_mod_path.to_string_lossy().replace(['/', '\\'], "-")
+        );
+        log::info!("Writing module badge '{file_name}' ...");
+        fs::write(
+            modules_dir.join(file_name),
+            badge::rating_badge(&mod_label, &rating, thresholds),
+        )?;
+        write_module_badges(mod_coverage, &full_mod_path, modules_dir, label, thresholds)?;
+    }
+    Ok(())
+}
+
+fn badge(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<()> {
+    if matches!(ctx.stds, Standards::All) {
+        anyhow::bail!(
+            "A badge can only show the rating of a single standard; \
+please use --{} or --{}",
+            cli::A_L_STANDARD,
+            cli::A_L_BEST_FIT
+        );
+    }
+    let mut listing_strm = input_stream(ctx.args)?;
+    let dirs_and_files = ctx.dirs_and_files(&mut listing_strm, false);
+
+    log::info!("Rating listing for the badge ...");
+    let coverages =
+        cover_listing_by_stds(dirs_and_files, &ctx.ignored_paths, ctx.policy, &ctx.stds)?;
+    let coverage = coverages
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("No standard to rate the listing with"))?;
+    let rating = Rating {
+        name: coverage.std.name.to_owned(),
+        factor: coverage.rate(),
+    };
+    let defaults = BadgeThresholds::default();
+    let thresholds = BadgeThresholds {
+        good: sub_com_args
+            .get_one::<f32>(cli::A_L_GOOD)
+            .copied()
+            .unwrap_or(defaults.good),
+        fair: sub_com_args
+            .get_one::<f32>(cli::A_L_FAIR)
+            .copied()
+            .unwrap_or(defaults.fair),
+    };
+    let label = sub_com_args
+        .get_one::<String>(cli::A_L_LABEL)
+        .map_or(badge::DEFAULT_LABEL, String::as_str);
+
+    out_stream.write_all(badge::rating_badge(label, &rating, thresholds).as_bytes())?;
+    if let Some(modules_dir) = sub_com_args.get_one::<PathBuf>(cli::A_L_MODULES_DIR) {
+        fs::create_dir_all(modules_dir)?;
+        write_module_badges(coverage, Path::new(""), modules_dir, label, thresholds)?;
+    }
+    Ok(())
+}
+
+fn install_hook(ctx: &Context, sub_com_args: &ArgMatches) -> anyhow::Result<()> {
+    let hook_file = git::hooks_dir()?.join("pre-commit");
+    if hook_file.exists() && !sub_com_args.get_flag(cli::A_L_FORCE) {
+        anyhow::bail!(
+            "A pre-commit hook already exists at '{}'; use --{} to overwrite it",
+            hook_file.display(),
+            cli::A_L_FORCE
+        );
+    }
+    let std_arg = if let Standards::Specific(std_name) = &ctx.stds {
+        format!(" --{} '{std_name}'", cli::A_L_STANDARD)
+    } else {
+        String::new()
+    };
+    log::info!(
+        "Installing pre-commit hook at '{}' ...",
+        hook_file.display()
+    );
+    if let Some(hooks_dir) = hook_file.parent() {
+        fs::create_dir_all(hooks_dir)?;
+    }
+    fs::write(
+        &hook_file,
+        format!(
+            "#!/bin/sh\n\
+# Installed by '{} {}'\n\
+exec {} --quiet{std_arg} {} --format text\n",
+            env!("CARGO_BIN_NAME"),
+            cli::SC_N_INSTALL_HOOK,
+            env!("CARGO_BIN_NAME"),
+            cli::SC_N_HOOK,
+        ),
+    )?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_file, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+fn show(
+    ctx: &Context,
+    sub_com_args: &ArgMatches,
+    out_stream: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let format_name = sub_com_args.get_one::<String>(cli::A_L_FORMAT);
+    let std = if let Some(std_file) = sub_com_args.get_one::<PathBuf>(cli::A_L_STD_FILE) {
+        log::info!("Reading standard from '{}' ...", std_file.display());
+        DirStandard::from_file(std_file, None)?
+    } else {
+        let std_name = match &ctx.stds {
+            Standards::Default => osh_dir_std::DEFAULT_STD_NAME,
+            Standards::Specific(std_name) => std_name.as_str(),
+            Standards::All | Standards::BestFit(_) => anyhow::bail!(
+                "'{}' shows exactly one standard; use --{}",
+                cli::SC_N_SHOW,
+                cli::A_L_STANDARD
+            ),
+        };
+        DirStandard::from(stds::by_name(std_name)?)
+    };
+    if let Some(graph_format) = format_name.and_then(|name| name.parse::<GraphFormat>().ok()) {
+        graph::write_std_graph(std.into_static(), graph_format, out_stream)?;
+        return Ok(());
+    }
+    let format = format_name.map_or_else(|| Ok(StdFormat::default()), |name| name.parse())?;
+    std.write(format, out_stream)?;
+    Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
@@ -208,50 +922,60 @@ fn main() -> anyhow::Result<()> {
         log_reload_handle.modify(|filter| *filter = LevelFilter::WARN)?;
     }
 
-    let ignored_paths = ignored_paths(args);
-    let pretty = true; // TODO Make this a CLI arg
+    let ctx = Context {
+        args,
+        ignored_paths: ignored_paths(args),
+        policy: match_policy(args),
+        listing_format: listing_format(args),
+        stds: standards(args),
+        tags: tags_filter(args),
+        rules: house_rules(args)?,
+        pretty: true, // TODO Make this a CLI arg
+    };
 
     if let Some((sub_com_name, sub_com_args)) = args.subcommand() {
-        let mut listing_strm = input_stream(args)?;
-        let dirs_and_files = dirs_and_files(&mut listing_strm);
-
-        let stds = standards(args);
-
         let mut out_stream = out_stream(args)?;
 
         match sub_com_name {
-            cli::SC_N_RATE => {
-                log::info!("Rating listing according to standard(s) ...");
-                let mut rating = rate_listing_by_stds(dirs_and_files, &ignored_paths, &stds)?;
-                let include_coverage = sub_com_args.get_flag(cli::A_L_INCLUDE_COVERAGE);
-                if !include_coverage {
-                    rating = rating
-                        .into_iter()
-                        .map(RatingCont::remove_coverage)
-                        .collect();
+            cli::SC_N_RATE => rate(&ctx, sub_com_args, &mut out_stream)?,
+            cli::SC_N_MAP => map(&ctx, sub_com_args, &mut out_stream)?,
+            cli::SC_N_DIFF => diff(&ctx, sub_com_args, &mut out_stream)?,
+            cli::SC_N_SHOW => show(&ctx, sub_com_args, &mut out_stream)?,
+            cli::SC_N_DRIFT => {
+                if drift(&ctx, sub_com_args, &mut out_stream)? {
+                    out_stream.flush()?;
+                    std::process::exit(1);
                 }
-
-                log::info!("Converting results to JSON ...");
-                let json_rating = if pretty {
-                    serde_json::to_string_pretty(&rating)
-                } else {
-                    serde_json::to_string(&rating)
-                }?;
-                out_stream.write_all(json_rating.as_bytes())?;
             }
-            cli::SC_N_MAP => {
-                log::info!("Mapping listing to standard(s) ...");
-                let coverage = cover_listing_by_stds(dirs_and_files, &ignored_paths, &stds)?;
-
-                let decorated_cov = coverage.into_iter().map(CovEntry::from).collect::<Vec<_>>();
-
-                log::info!("Converting results to JSON ...");
-                let json_coverage = if pretty {
-                    serde_json::to_string_pretty(&decorated_cov)
-                } else {
-                    serde_json::to_string(&decorated_cov)
-                }?;
-                out_stream.write_all(json_coverage.as_bytes())?;
+            cli::SC_N_HOOK => {
+                if hook(&ctx, sub_com_args, &mut out_stream)? {
+                    out_stream.flush()?;
+                    std::process::exit(1);
+                }
+            }
+            cli::SC_N_CHECK => {
+                if check(&ctx, sub_com_args, &mut out_stream)? {
+                    out_stream.flush()?;
+                    std::process::exit(1);
+                }
+            }
+            cli::SC_N_ANNOTATE => annotate(&ctx, sub_com_args, &mut out_stream)?,
+            cli::SC_N_BADGE => badge(&ctx, sub_com_args, &mut out_stream)?,
+            cli::SC_N_INSTALL_HOOK => install_hook(&ctx, sub_com_args)?,
+            #[cfg(feature = "serve")]
+            cli::SC_N_SERVE => serve::run(
+                &ctx,
+                sub_com_args
+                    .get_one::<String>(cli::A_L_BIND)
+                    .map_or(serve::DEFAULT_BIND, String::as_str),
+            )?,
+            #[cfg(feature = "tui")]
+            cli::SC_N_TUI => tui::run(&ctx)?,
+            cli::SC_N_LINT_STD => {
+                if lint_std(&ctx, sub_com_args, &mut out_stream)? {
+                    out_stream.flush()?;
+                    std::process::exit(1);
+                }
             }
             _ => {
                 error!("Sub-command not implemented: '{sub_com_name}'");
@@ -0,0 +1,61 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::io::{self, Write};
+
+use osh_dir_std::{MappingRow, PathMapping};
+
+use crate::cli;
+
+/// Writes path mappings one by one, in the chosen format.
+pub enum MappingWriter<'a> {
+    /// One [`PathMapping`] as JSON object per line
+    Json(&'a mut dyn Write),
+    /// One [`MappingRow`] as JSON object per line
+    NdJson(&'a mut dyn Write),
+    /// One [`MappingRow`] per line, with a header line
+    Csv(Box<csv::Writer<&'a mut dyn Write>>),
+}
+
+impl<'a> MappingWriter<'a> {
+    pub fn new(format: &str, out_stream: &'a mut dyn Write) -> Self {
+        match format {
+            cli::FORMAT_NDJSON => Self::NdJson(out_stream),
+            cli::FORMAT_CSV => Self::Csv(Box::new(csv::Writer::from_writer(out_stream))),
+            _ => Self::Json(out_stream),
+        }
+    }
+
+    /// Writes a single mapping.
+    ///
+    /// # Errors
+    ///
+    /// If serializing or writing failed.
+    pub fn write(&mut self, mapping: &PathMapping) -> io::Result<()> {
+        match self {
+            Self::Json(out_stream) => {
+                let json_mapping = serde_json::to_string(mapping).map_err(io::Error::other)?;
+                writeln!(out_stream, "{json_mapping}")
+            }
+            Self::NdJson(out_stream) => {
+                let json_row =
+                    serde_json::to_string(&MappingRow::from(mapping)).map_err(io::Error::other)?;
+                writeln!(out_stream, "{json_row}")
+            }
+            Self::Csv(csv_writer) => Ok(csv_writer.serialize(MappingRow::from(mapping))?),
+        }
+    }
+
+    /// Flushes all buffered output.
+    ///
+    /// # Errors
+    ///
+    /// If writing failed.
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Json(out_stream) | Self::NdJson(out_stream) => out_stream.flush(),
+            Self::Csv(mut csv_writer) => csv_writer.flush(),
+        }
+    }
+}
@@ -0,0 +1,431 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! A local HTTP service that rates, maps and checks listings,
+//! so tools and editors can use the standards
+//! without spawning a process per request.
+//!
+//! Endpoints:
+//!
+//! - `GET /standards` - the names of all known standards
+//! - `GET /standards/{name}` - one standard, with all its records
+//! - `POST /rate` - like the `rate` sub-command
+//! - `POST /map` - like the `map` sub-command (JSON report)
+//! - `POST /check` - the checks of the `annotate` sub-command
+//!
+//! The `POST` endpoints take a listing as request body,
+//! and accept a `standard` query parameter
+//! (a standard name, `all` or `best-fit`),
+//! which defaults to the standard(s) given on the command-line.
+//! The listing format is chosen by the `Content-Type`:
+//! `application/json` - a JSON array of paths;
+//! `application/x-ndjson` - see [`ListingFormat::JsonLines`];
+//! anything else - new-line separated paths.
+
+use std::{
+    collections::BTreeMap,
+    io::{self, BufReader},
+    path::PathBuf,
+    rc::Rc,
+};
+
+use osh_dir_std::{
+    ci, cover_listing_by_stds,
+    data::STD_NAMES,
+    format::DirStandard,
+    listing::{self, ListingFormat, ListingSource},
+    rate_listing, rate_listing_by_stds,
+    stds::{self, Standards},
+    tags, BestFitThresholds, Ranking, RatingCont, Report, DEFAULT_STD_NAME,
+};
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::Context;
+
+pub const DEFAULT_BIND: &str = "127.0.0.1:8080";
+
+const CONTENT_TYPE_JSON: &str = "application/json";
+const CONTENT_TYPE_NDJSON: &str = "application/x-ndjson";
+
+/// What went wrong while handling a request,
+/// reported to the client as `{"error": "..."}`.
+struct HttpError {
+    status: u16,
+    message: String,
+}
+
+impl HttpError {
+    fn bad_request<E: ToString>(err: &E) -> Self {
+        Self {
+            status: 400,
+            message: err.to_string(),
+        }
+    }
+
+    const fn not_found(message: String) -> Self {
+        Self {
+            status: 404,
+            message,
+        }
+    }
+}
+
+impl From<osh_dir_std::Error> for HttpError {
+    fn from(err: osh_dir_std::Error) -> Self {
+        Self::bad_request(&err)
+    }
+}
+
+impl From<io::Error> for HttpError {
+    fn from(err: io::Error) -> Self {
+        Self::bad_request(&err)
+    }
+}
+
+#[derive(Serialize)]
+struct StdSummary {
+    name: &'static str,
+    default: bool,
+    records: usize,
+}
+
+/// The standards, converted for serving once at start-up,
+/// and then shared by all requests.
+struct Registry {
+    summaries: Vec<StdSummary>,
+    stds: BTreeMap<&'static str, DirStandard>,
+}
+
+impl Registry {
+    fn load() -> Result<Self, osh_dir_std::Error> {
+        let mut summaries = vec![];
+        let mut stds = BTreeMap::new();
+        for std_name in STD_NAMES {
+            let std = stds::by_name(std_name)?;
+            summaries.push(StdSummary {
+                name: std.name,
+                default: std.name == DEFAULT_STD_NAME,
+                records: std.records.len(),
+            });
+            stds.insert(std.name, DirStandard::from(std));
+        }
+        Ok(Self { summaries, stds })
+    }
+}
+
+/// Splits the URL into the path and the value of the `standard` query parameter.
+fn split_url(url: &str) -> (&str, Option<&str>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let std_param = query
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _value)| *key == "standard")
+        .map(|(_key, value)| value);
+    (path, std_param)
+}
+
+fn standards(ctx: &Context, std_param: Option<&str>) -> Standards {
+    match std_param {
+        None => ctx.stds.clone(),
+        Some("all") => Standards::All,
+        Some("best-fit") => {
+            if let Standards::BestFit(thresholds) = &ctx.stds {
+                Standards::BestFit(*thresholds)
+            } else {
+                Standards::BestFit(BestFitThresholds::default())
+            }
+        }
+        Some(std_name) => Standards::Specific(std_name.to_owned()),
+    }
+}
+
+fn content_type(request: &Request) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Content-Type"))
+        .map(|header| header.value.as_str().to_owned())
+}
+
+/// Reads the listing from the request body,
+/// including the ancestor dirs of all the listed paths.
+fn read_listing(request: &mut Request) -> Result<Vec<Rc<PathBuf>>, HttpError> {
+    let content_type = content_type(request).unwrap_or_default();
+    let reader = BufReader::new(request.as_reader());
+    let dirs_and_files = if content_type.starts_with(CONTENT_TYPE_JSON) {
+        let paths: Vec<PathBuf> =
+            serde_json::from_reader(reader).map_err(|err| HttpError::bad_request(&err))?;
+        listing::from_paths(paths)
+            .dirs_and_files(false)
+            .collect::<io::Result<_>>()?
+    } else {
+        let format = if content_type.starts_with(CONTENT_TYPE_NDJSON) {
+            ListingFormat::JsonLines
+        } else {
+            ListingFormat::Lines
+        };
+        format
+            .source(reader)
+            .dirs_and_files(false)
+            .collect::<io::Result<_>>()?
+    };
+    Ok(dirs_and_files)
+}
+
+fn to_json<T: Serialize>(ctx: &Context, value: &T) -> Result<String, HttpError> {
+    if ctx.pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+    .map_err(|err| HttpError {
+        status: 500,
+        message: err.to_string(),
+    })
+}
+
+fn rate(ctx: &Context, stds: &Standards, listing: Vec<Rc<PathBuf>>) -> Result<String, HttpError> {
+    let dirs_and_files = listing.into_iter().map(Ok);
+    if let Standards::BestFit(thresholds) = stds {
+        let ratings = rate_listing(dirs_and_files, &ctx.ignored_paths, ctx.policy)?;
+        return to_json(ctx, &Ranking::new(ratings, thresholds).remove_coverages());
+    }
+    let ratings = rate_listing_by_stds(dirs_and_files, &ctx.ignored_paths, ctx.policy, stds)?
+        .into_iter()
+        .map(RatingCont::remove_coverage)
+        .collect::<Vec<_>>();
+    to_json(ctx, &ratings)
+}
+
+fn map(ctx: &Context, stds: &Standards, listing: Vec<Rc<PathBuf>>) -> Result<String, HttpError> {
+    let coverages = cover_listing_by_stds(
+        listing.into_iter().map(Ok),
+        &ctx.ignored_paths,
+        ctx.policy,
+        stds,
+    )?;
+    let mut report = Report::new(&coverages);
+    if let Some(tags) = &ctx.tags {
+        report.retain_tags(tags);
+    }
+    to_json(ctx, &report)
+}
+
+fn check(ctx: &Context, stds: &Standards, listing: Vec<Rc<PathBuf>>) -> Result<String, HttpError> {
+    let coverages = cover_listing_by_stds(
+        listing.into_iter().map(Ok),
+        &ctx.ignored_paths,
+        ctx.policy,
+        stds,
+    )?;
+    let mut checks = coverages.iter().flat_map(ci::checks).collect::<Vec<_>>();
+    if let Some(tags) = &ctx.tags {
+        checks.retain(|check| tags::any_in_filter(check.tags.iter().copied(), tags));
+    }
+    to_json(ctx, &checks)
+}
+
+fn handle(ctx: &Context, registry: &Registry, request: &mut Request) -> Result<String, HttpError> {
+    let url = request.url().to_owned();
+    let (path, std_param) = split_url(&url);
+    match (request.method(), path) {
+        (Method::Get, "/standards") => to_json(ctx, &registry.summaries),
+        (Method::Get, _) if path.starts_with("/standards/") => {
+            let std_name = path.trim_start_matches("/standards/");
+            let std = registry.stds.get(std_name).ok_or_else(|| {
+                HttpError::not_found(osh_dir_std::Error::unknown_standard(std_name).to_string())
+            })?;
+            to_json(ctx, std)
+        }
+        (Method::Post, "/rate" | "/map" | "/check") => {
+            let stds = standards(ctx, std_param);
+            let listing = read_listing(request)?;
+            log::info!(
+                "Serving {path} for {} listed path(s), standard(s): {stds} ...",
+                listing.len()
+            );
+            match path {
+                "/rate" => rate(ctx, &stds, listing),
+                "/map" => map(ctx, &stds, listing),
+                _ => check(ctx, &stds, listing),
+            }
+        }
+        (method, _) => Err(HttpError::not_found(format!(
+            "No such endpoint: {method} {path}"
+        ))),
+    }
+}
+
+fn json_header() -> Header {
+    Header::from_bytes("Content-Type", CONTENT_TYPE_JSON)
+        .expect("Static header is valid; this should never happen")
+}
+
+/// Handles a single request,
+/// and returns the status code and the (JSON) body of the response.
+fn respond(ctx: &Context, registry: &Registry, request: &mut Request) -> (u16, String) {
+    match handle(ctx, registry, request) {
+        Ok(body) => (200, body),
+        Err(err) => {
+            log::warn!("{} {}: {}", request.method(), request.url(), err.message);
+            (
+                err.status,
+                serde_json::json!({ "error": err.message }).to_string(),
+            )
+        }
+    }
+}
+
+/// Serves requests on the given address until the process is killed.
+/// Requests are handled one after the other.
+///
+/// # Errors
+///
+/// If the standards could not be loaded,
+/// or the address could not be bound.
+pub fn run(ctx: &Context, bind: &str) -> anyhow::Result<()> {
+    let registry = Registry::load()?;
+    let server =
+        Server::http(bind).map_err(|err| anyhow::anyhow!("Failed to bind to {bind}: {err}"))?;
+    log::info!("Serving on http://{bind}/ ...");
+    for mut request in server.incoming_requests() {
+        let (status, body) = respond(ctx, &registry, &mut request);
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(json_header());
+        if let Err(err) = request.respond(response) {
+            log::warn!("Failed to send response: {err}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use tiny_http::TestRequest;
+
+    use super::*;
+
+    /// Handles the request with the settings of a plain `serve`,
+    /// and returns the status code and the parsed response body.
+    fn serve(request: TestRequest) -> (u16, Value) {
+        let args = crate::cli::arg_matcher().get_matches_from(["osh-dir-std", "serve"]);
+        let ctx = Context {
+            args: &args,
+            ignored_paths: crate::ignored_paths(&args),
+            policy: crate::match_policy(&args),
+            listing_format: crate::listing_format(&args),
+            stds: crate::standards(&args),
+            tags: None,
+            rules: osh_dir_std::rules::Registry::new(),
+            pretty: false,
+        };
+        let registry = Registry::load().expect("Built-in standards are valid");
+        let (status, body) = respond(&ctx, &registry, &mut request.into());
+        let json = serde_json::from_str(&body).expect("Responses are always JSON");
+        (status, json)
+    }
+
+    fn post(url: &str, content_type: Option<&str>, body: &'static str) -> TestRequest {
+        let request = TestRequest::new()
+            .with_method(Method::Post)
+            .with_path(url)
+            .with_body(body);
+        match content_type {
+            Some(value) => request
+                .with_header(Header::from_bytes("Content-Type", value).expect("Valid test header")),
+            None => request,
+        }
+    }
+
+    #[test]
+    fn url_query() {
+        assert_eq!(split_url("/rate"), ("/rate", None));
+        assert_eq!(
+            split_url("/rate?pretty=1&standard=best-fit"),
+            ("/rate", Some("best-fit"))
+        );
+        assert_eq!(split_url("/map?standard"), ("/map", None));
+    }
+
+    #[test]
+    fn rate_lines() {
+        let (status, json) = serve(post("/rate", None, "README.md\nsrc/main.c\n"));
+        assert_eq!(status, 200);
+        assert_eq!(
+            json.pointer("/0/rating/name").and_then(Value::as_str),
+            Some(DEFAULT_STD_NAME)
+        );
+        assert_eq!(json.as_array().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn rate_json_best_fit() {
+        let (status, json) = serve(post(
+            "/rate?standard=best-fit",
+            Some("application/json; charset=utf-8"),
+            r#"["README.md", "src/main.c", "doc/index.md"]"#,
+        ));
+        assert_eq!(status, 200);
+        assert!(json.get("verdict").is_some_and(Value::is_string));
+        assert_eq!(
+            json.get("ratings").and_then(Value::as_array).map(Vec::len),
+            Some(STD_NAMES.len())
+        );
+    }
+
+    #[test]
+    fn map_ndjson() {
+        let (status, json) = serve(post(
+            "/map?standard=all",
+            Some(CONTENT_TYPE_NDJSON),
+            "{\"path\": \"README.md\"}\n{\"path\": \"doc/index.md\"}\n",
+        ));
+        assert_eq!(status, 200);
+        assert_eq!(
+            json.pointer("/input/num_paths").and_then(Value::as_u64),
+            Some(3)
+        );
+        assert_eq!(
+            json.get("coverages")
+                .and_then(Value::as_array)
+                .map(Vec::len),
+            Some(STD_NAMES.len())
+        );
+    }
+
+    #[test]
+    fn standards_listed() {
+        let (status, json) = serve(TestRequest::new().with_path("/standards"));
+        assert_eq!(status, 200);
+        assert!(json
+            .as_array()
+            .is_some_and(|summaries| summaries.iter().any(|summary| summary
+                .get("name")
+                .and_then(Value::as_str)
+                == Some(DEFAULT_STD_NAME)
+                && summary.get("default") == Some(&Value::Bool(true)))));
+    }
+
+    #[test]
+    fn errors() {
+        let (status, json) = serve(post("/rate?standard=no-such-std", None, "README.md\n"));
+        assert_eq!(status, 400);
+        assert!(json
+            .get("error")
+            .and_then(Value::as_str)
+            .is_some_and(|msg| msg.contains("no-such-std")));
+
+        let (status, _json) = serve(post("/rate", Some(CONTENT_TYPE_JSON), "README.md"));
+        assert_eq!(status, 400);
+
+        let (status, _json) = serve(TestRequest::new().with_path("/standards/no-such-std"));
+        assert_eq!(status, 404);
+
+        let (status, _json) = serve(post("/no-such-endpoint", None, ""));
+        assert_eq!(status, 404);
+    }
+}
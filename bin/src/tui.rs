@@ -0,0 +1,572 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! An interactive terminal UI to browse how a listing is covered
+//! by the known standards,
+//! showing the project tree next to the record tree of a standard.
+
+use std::{
+    cmp::Ordering,
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use osh_dir_std::{
+    data::STD_NAMES,
+    format::{DirStd, Rec},
+    stds::{self, Standards},
+    tree::{self, RNode},
+    Checker, PathMapping, Ranking, Rating, RatingCont, Status, DEFAULT_STD_NAME,
+};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use crate::Context;
+
+const PAGE_SIZE: usize = 20;
+
+const fn status_color(status: Status) -> Color {
+    match status {
+        Status::In => Color::Green,
+        Status::Module => Color::Cyan,
+        Status::Out => Color::Red,
+        Status::Ignored => Color::DarkGray,
+        Status::ArbitraryContent => Color::Yellow,
+        Status::GeneratedContent => Color::Magenta,
+    }
+}
+
+/// How a path of the listing is shown in the project tree:
+/// coloured by its status, and underlined if it is misplaced.
+const fn path_style(mapping: &PathMapping) -> Style {
+    let style = Style::new().fg(status_color(mapping.status));
+    if mapping.misplaced.is_some() {
+        style.add_modifier(Modifier::UNDERLINED)
+    } else {
+        style
+    }
+}
+
+/// The file name of the path, indented by its depth.
+fn path_label(path: &Path) -> String {
+    let depth = path.components().count().saturating_sub(1);
+    let name = path
+        .file_name()
+        .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy());
+    format!("{}{name}", "  ".repeat(depth))
+}
+
+/// One line in the record tree of a standard.
+struct RecordLine {
+    depth: usize,
+    name: String,
+    rec: Option<&'static Rec<'static>>,
+}
+
+fn collect_record_lines(node: &RNode<'static>, depth: usize, lines: &mut Vec<RecordLine>) {
+    let node_ref = node.borrow();
+    let mut children = node_ref.children.iter().collect::<Vec<_>>();
+    children.sort_by_key(|(name, _child)| *name);
+    for (name, child) in children {
+        let rec = child.borrow().value;
+        lines.push(RecordLine {
+            depth,
+            name: if rec.is_some_and(|node_rec| node_rec.directory) {
+                format!("{name}/")
+            } else {
+                name.clone()
+            },
+            rec,
+        });
+        collect_record_lines(child, depth + 1, lines);
+    }
+}
+
+impl RecordLine {
+    fn is_matched_by(&self, mapping: Option<&PathMapping>) -> bool {
+        self.rec
+            .zip(mapping)
+            .is_some_and(|(rec, sel_mapping)| sel_mapping.records.contains(&rec))
+    }
+
+    /// How the record is shown in the record tree:
+    /// highlighted if it matches the selected path,
+    /// greyed out if it is only an intermediate dir,
+    /// and bold if it is normative.
+    fn style(&self, matched: bool) -> Style {
+        let style = if self.rec.is_some_and(|rec| rec.normative) {
+            Style::new().add_modifier(Modifier::BOLD)
+        } else {
+            Style::new()
+        };
+        if matched {
+            style.fg(Color::Black).bg(Color::Green)
+        } else if self.rec.is_none() {
+            style.fg(Color::DarkGray)
+        } else {
+            style
+        }
+    }
+
+    fn label(&self) -> String {
+        format!("{}{}", "  ".repeat(self.depth), self.name)
+    }
+}
+
+/// Everything shown for one standard.
+struct StdView {
+    std: &'static DirStd,
+    rating: f32,
+    /// One for each path of the listing, in the same order
+    mappings: Vec<PathMapping>,
+    records: Vec<RecordLine>,
+}
+
+impl StdView {
+    fn new(ctx: &Context, std: &'static DirStd, listing: &[Rc<PathBuf>]) -> anyhow::Result<Self> {
+        let mut checker = Checker::new(std, &ctx.ignored_paths, ctx.policy)?;
+        let mappings = listing
+            .iter()
+            .map(|dir_or_file| checker.cover(dir_or_file))
+            .collect();
+        let rating = checker.coverage().rate();
+        let (root, _rec_nodes) = tree::create(std, ctx.policy)?;
+        let mut records = vec![];
+        collect_record_lines(&root, 0, &mut records);
+        Ok(Self {
+            std,
+            rating,
+            mappings,
+            records,
+        })
+    }
+
+    /// The index of the first record matching the given path mapping.
+    fn first_matched(&self, mapping: Option<&PathMapping>) -> Option<usize> {
+        self.records
+            .iter()
+            .position(|line| line.is_matched_by(mapping))
+    }
+}
+
+struct App {
+    listing: Vec<Rc<PathBuf>>,
+    views: Vec<StdView>,
+    view_idx: usize,
+    paths_state: ListState,
+    records_state: ListState,
+}
+
+impl App {
+    fn view(&self) -> &StdView {
+        #[allow(clippy::indexing_slicing)]
+        &self.views[self.view_idx]
+    }
+
+    fn selected(&self) -> Option<&PathMapping> {
+        self.paths_state
+            .selected()
+            .and_then(|idx| self.view().mappings.get(idx))
+    }
+
+    const fn switch_std(&mut self, forward: bool) {
+        let num = self.views.len();
+        self.view_idx = if forward {
+            (self.view_idx + 1) % num
+        } else {
+            (self.view_idx + num - 1) % num
+        };
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let Some(last) = self.listing.len().checked_sub(1) else {
+            return;
+        };
+        let current = self.paths_state.selected().unwrap_or(0);
+        let target = current.saturating_add_signed(delta).min(last);
+        self.paths_state.select(Some(target));
+    }
+
+    /// Returns whether to quit.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(PAGE_SIZE.cast_signed()),
+            KeyCode::PageUp => self.move_selection(-PAGE_SIZE.cast_signed()),
+            KeyCode::Home | KeyCode::Char('g') => self.paths_state.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX),
+            KeyCode::Tab | KeyCode::Char('s') => self.switch_std(true),
+            KeyCode::BackTab | KeyCode::Char('S') => self.switch_std(false),
+            KeyCode::Backspace
+            | KeyCode::Enter
+            | KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::Delete
+            | KeyCode::Insert
+            | KeyCode::F(_)
+            | KeyCode::Char(_)
+            | KeyCode::Null
+            | KeyCode::CapsLock
+            | KeyCode::ScrollLock
+            | KeyCode::NumLock
+            | KeyCode::PrintScreen
+            | KeyCode::Pause
+            | KeyCode::Menu
+            | KeyCode::KeypadBegin
+            | KeyCode::Media(_)
+            | KeyCode::Modifier(_) => (),
+        }
+        false
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header_area, main_area, details_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(5),
+            Constraint::Length(9),
+        ])
+        .areas(frame.area());
+        let [paths_area, records_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(main_area);
+
+        self.draw_header(frame, header_area);
+        self.draw_paths(frame, paths_area);
+        self.draw_records(frame, records_area);
+        self.draw_details(frame, details_area);
+    }
+
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let view = self.view();
+        let header = Line::from(vec![
+            Span::styled(
+                format!(" {} ", view.std.name),
+                Style::new().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            ),
+            Span::raw(format!(
+                " ({}/{}) rating: {:.2}   ",
+                self.view_idx + 1,
+                self.views.len(),
+                view.rating
+            )),
+            Span::styled(
+                "[Tab/S] switch standard  [\u{2191}\u{2193}/PgUp/PgDn] move  [q] quit",
+                Style::new().fg(Color::DarkGray),
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(header), area);
+    }
+
+    fn draw_paths(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .listing
+            .iter()
+            .zip(&self.view().mappings)
+            .map(|(path, mapping)| {
+                ListItem::new(Line::styled(path_label(path), path_style(mapping)))
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Project "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.paths_state);
+    }
+
+    fn draw_records(&mut self, frame: &mut Frame, area: Rect) {
+        let selected = self.selected();
+        let view = self.view();
+        let items = view
+            .records
+            .iter()
+            .map(|line| {
+                ListItem::new(Line::styled(
+                    line.label(),
+                    line.style(line.is_matched_by(selected)),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let first_matched = view.first_matched(selected);
+        let list = List::new(items).block(Block::bordered().title(format!(
+            " Records of '{}' (bold: normative) ",
+            view.std.name
+        )));
+        // Scrolls to the (first) matched record
+        self.records_state.select(first_matched);
+        frame.render_stateful_widget(list, area, &mut self.records_state);
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let mut lines = vec![];
+        if let Some(mapping) = self.selected() {
+            lines.push(Line::from(vec![
+                Span::raw("Path: "),
+                Span::styled(
+                    mapping.path.to_string_lossy().into_owned(),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+            ]));
+            let mut status = vec![
+                Span::raw("Status: "),
+                Span::styled(
+                    mapping.status.name(),
+                    Style::new().fg(status_color(mapping.status)),
+                ),
+            ];
+            if mapping.generated_content && mapping.status != Status::GeneratedContent {
+                status.push(Span::raw(" (generated content)"));
+            }
+            if let Some(misplaced) = mapping.misplaced {
+                status.push(Span::styled(
+                    format!(
+                        " misplaced: '{}' expects {}",
+                        misplaced.path,
+                        misplaced
+                            .file_types
+                            .iter()
+                            .flatten()
+                            .copied()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    Style::new().fg(Color::Red),
+                ));
+            }
+            lines.push(Line::from(status));
+            if let Some(module_root) = mapping.module_root() {
+                lines.push(Line::raw(format!("Module: {}", module_root.display())));
+            }
+            for rec in &mapping.records {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("Record '{}': ", rec.path),
+                        Style::new().fg(Color::Green),
+                    ),
+                    Span::raw(rec.description),
+                ]));
+            }
+        }
+        let details = Paragraph::new(lines)
+            .block(Block::bordered().title(" Details "))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(details, area);
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && self.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// The index of the standard to show first;
+/// with [`Standards::BestFit`], the one chosen by the [`Ranking`].
+fn initial_view(stds: &Standards, views: &[StdView]) -> usize {
+    let position_of = |std_name: &str| {
+        views
+            .iter()
+            .position(|view| view.std.name == std_name)
+            .unwrap_or(0)
+    };
+    match stds {
+        Standards::Default => position_of(DEFAULT_STD_NAME),
+        Standards::Specific(std_name) => position_of(std_name),
+        Standards::BestFit(thresholds) => {
+            let ratings = views
+                .iter()
+                .map(|view| RatingCont {
+                    rating: Rating {
+                        name: view.std.name.to_owned(),
+                        factor: view.rating,
+                    },
+                    coverage: None,
+                })
+                .collect();
+            Ranking::new(ratings, thresholds)
+                .chosen
+                .map_or(0, |std_name| position_of(&std_name))
+        }
+        Standards::All => views
+            .iter()
+            .enumerate()
+            .max_by(|(_, view_a), (_, view_b)| {
+                view_a
+                    .rating
+                    .partial_cmp(&view_b.rating)
+                    .unwrap_or(Ordering::Equal)
+            })
+            .map_or(0, |(idx, _view)| idx),
+    }
+}
+
+/// Reads the listing, checks it with all known standards,
+/// and lets the user browse the results until they quit.
+///
+/// # Errors
+///
+/// If reading the listing failed,
+/// a standard could not be used,
+/// or drawing to the terminal failed.
+pub fn run(ctx: &Context) -> anyhow::Result<()> {
+    let mut listing_strm = crate::input_stream(ctx.args)?;
+    let mut listing = ctx
+        .dirs_and_files(&mut listing_strm, false)
+        .collect::<io::Result<Vec<_>>>()?;
+    listing.sort();
+    listing.dedup();
+    if let Standards::Specific(std_name) = &ctx.stds {
+        // Fail early on unknown standards
+        stds::by_name(std_name)?;
+    }
+    log::info!("Checking the listing against all standards ...");
+    let views = STD_NAMES
+        .iter()
+        .map(|std_name| StdView::new(ctx, stds::by_name(std_name)?, &listing))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let view_idx = initial_view(&ctx.stds, &views);
+    let mut app = App {
+        listing,
+        views,
+        view_idx,
+        paths_state: ListState::default().with_selected(Some(0)),
+        records_state: ListState::default(),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    Ok(result?)
+}
+
+#[cfg(test)]
+mod tests {
+    use osh_dir_std::{constants, matching::MatchPolicy, BestFitThresholds};
+
+    use super::*;
+
+    fn unixish() -> &'static DirStd {
+        stds::by_name("unixish").expect("unixish is a built-in standard")
+    }
+
+    fn cover(path: &str) -> PathMapping {
+        Checker::new(
+            unixish(),
+            &constants::DEFAULT_IGNORED_PATHS,
+            MatchPolicy::default(),
+        )
+        .expect("unixish is valid")
+        .cover(&Rc::new(PathBuf::from(path)))
+    }
+
+    fn record_view() -> StdView {
+        let (root, _rec_nodes) =
+            tree::create(unixish(), MatchPolicy::default()).expect("unixish is valid");
+        let mut records = vec![];
+        collect_record_lines(&root, 0, &mut records);
+        StdView {
+            std: unixish(),
+            rating: 0.0,
+            mappings: vec![],
+            records,
+        }
+    }
+
+    #[test]
+    fn path_labels() {
+        assert_eq!(path_label(Path::new("README.md")), "README.md");
+        assert_eq!(path_label(Path::new("doc/img/x.png")), "    x.png");
+    }
+
+    #[test]
+    fn path_colors() {
+        assert_eq!(path_style(&cover("README.md")).fg, Some(Color::Green));
+        assert_eq!(path_style(&cover("weird.txt")).fg, Some(Color::Red));
+        assert_eq!(path_style(&cover(".git")).fg, Some(Color::DarkGray));
+    }
+
+    #[test]
+    fn record_lines_sorted() {
+        let view = record_view();
+        let top_level = view
+            .records
+            .iter()
+            .filter(|line| line.depth == 0)
+            .map(|line| line.name.as_str())
+            .collect::<Vec<_>>();
+        let mut sorted = top_level.clone();
+        sorted.sort_unstable_by_key(|name| name.trim_end_matches('/'));
+        assert_eq!(top_level, sorted);
+        assert!(top_level.contains(&"doc/"));
+        assert!(top_level.contains(&"README.md"));
+    }
+
+    /// A view of each known standard, rated as given by `rate`.
+    fn rated_views(rate: impl Fn(&str) -> f32) -> Vec<StdView> {
+        STD_NAMES
+            .iter()
+            .map(|std_name| {
+                let std = stds::by_name(std_name).expect("Known standard");
+                StdView {
+                    std,
+                    rating: rate(std.name),
+                    mappings: vec![],
+                    records: vec![],
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn initial_view_best_fit() {
+        let other = STD_NAMES
+            .iter()
+            .copied()
+            .find(|std_name| *std_name != DEFAULT_STD_NAME)
+            .expect("There is more than one standard");
+        let best_fit = Standards::BestFit(BestFitThresholds::default());
+        let name_of = |views: &[StdView], idx: usize| views.get(idx).map(|view| view.std.name);
+
+        // Ambiguous, so the default standard is chosen
+        let views = rated_views(|std_name| if std_name == other { 0.6 } else { 0.58 });
+        assert_eq!(
+            name_of(&views, initial_view(&best_fit, &views)),
+            Some(DEFAULT_STD_NAME)
+        );
+
+        let views = rated_views(|std_name| if std_name == other { 0.9 } else { 0.1 });
+        assert_eq!(
+            name_of(&views, initial_view(&best_fit, &views)),
+            Some(other)
+        );
+    }
+
+    #[test]
+    fn record_lookup() {
+        let view = record_view();
+        let readme = cover("README.md");
+        let idx = view
+            .first_matched(Some(&readme))
+            .expect("README.md matches a record");
+        let line = view.records.get(idx).expect("valid index");
+        assert_eq!(line.name, "README.md");
+        assert_eq!(line.style(true).bg, Some(Color::Green));
+        assert!(line.style(false).add_modifier.contains(Modifier::BOLD));
+
+        assert_eq!(view.first_matched(Some(&cover("weird.txt"))), None);
+        assert_eq!(view.first_matched(None), None);
+    }
+}
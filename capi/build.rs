@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{env, process};
+
+const HEADER_FILE: &str = "include/osh_dir_std.h";
+
+/// Generates the C header for the API in `src/lib.rs`.
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").expect("Cargo always sets this");
+    let config = match cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")) {
+        Ok(config) => config,
+        Err(err) => {
+            println!("cargo:warning=Failed to read cbindgen.toml: {err}");
+            process::exit(2);
+        }
+    };
+    match cbindgen::generate_with_config(&crate_dir, config) {
+        Ok(bindings) => {
+            bindings.write_to_file(format!("{crate_dir}/{HEADER_FILE}"));
+        }
+        Err(err) => {
+            println!("cargo:warning=Failed to generate the C header: {err}");
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+#ifndef OSH_DIR_STD_H
+#define OSH_DIR_STD_H
+
+/* This file is generated by cbindgen (see build.rs); do not edit it manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Checks paths against a standard, one at a time.
+typedef struct OshDirStdChecker OshDirStdChecker;
+
+// Which paths fed to a checker are covered by which records of its standard.
+typedef struct OshDirStdCoverage OshDirStdCoverage;
+
+// A directory standard;
+// owned by the library, and valid for the whole runtime of the program.
+typedef struct OshDirStdStandard OshDirStdStandard;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the message of the last error that occurred in this thread,
+// or `NULL` if there was none.
+// The string is owned by the library,
+// and valid until the next failing call in the same thread.
+const char *osh_dir_std_last_error(void);
+
+// Returns the version of the library.
+// The string is owned by the library.
+const char *osh_dir_std_version(void);
+
+// Returns the number of known standards.
+size_t osh_dir_std_standards_count(void);
+
+// Returns the known standard at the given index,
+// or `NULL` if the index is out of range.
+const struct OshDirStdStandard *osh_dir_std_standard_at(size_t index);
+
+// Returns the default standard.
+const struct OshDirStdStandard *osh_dir_std_default_standard(void);
+
+// Returns the standard with the given name,
+// or `NULL` if there is none.
+//
+// # Safety
+//
+// `name` must be a valid, NUL terminated C string.
+const struct OshDirStdStandard *osh_dir_std_standard_by_name(const char *name);
+
+// Returns the name of the standard.
+// The string is owned by the library.
+//
+// # Safety
+//
+// `standard` must be a pointer returned by one of the `osh_dir_std_standard*` functions.
+const char *osh_dir_std_standard_name(const struct OshDirStdStandard *standard);
+
+// Creates a checker for the standard.
+//
+// Paths matching `ignored_paths_regex` are ignored;
+// if it is `NULL`, hidden files and dirs are ignored.
+// Returns `NULL` on error.
+// The checker has to be released with either
+// [`osh_dir_std_checker_finish`] or [`osh_dir_std_checker_free`].
+//
+// # Safety
+//
+// `standard` must be a pointer returned by one of the `osh_dir_std_standard*` functions,
+// and `ignored_paths_regex` must be `NULL` or a valid, NUL terminated C string.
+struct OshDirStdChecker *osh_dir_std_checker_new(const struct OshDirStdStandard *standard,
+                                                 const char *ignored_paths_regex);
+
+// Feeds a single path, relative to the project root, to the checker.
+// Its ancestor dirs are added automatically,
+// and paths that were already fed are skipped.
+// Returns 0 on success, and -1 on error.
+//
+// # Safety
+//
+// `checker` must be a pointer returned by [`osh_dir_std_checker_new`],
+// and `path` a valid, NUL terminated C string.
+int osh_dir_std_checker_add_path(struct OshDirStdChecker *checker, const char *path);
+
+// Releases the checker, and returns the coverage of all the paths fed to it.
+// The coverage has to be released with [`osh_dir_std_coverage_free`].
+//
+// # Safety
+//
+// `checker` must be a pointer returned by [`osh_dir_std_checker_new`],
+// and must not be used afterwards.
+struct OshDirStdCoverage *osh_dir_std_checker_finish(struct OshDirStdChecker *checker);
+
+// Releases a checker without evaluating it.
+//
+// # Safety
+//
+// `checker` must be `NULL` or a pointer returned by [`osh_dir_std_checker_new`],
+// and must not be used afterwards.
+void osh_dir_std_checker_free(struct OshDirStdChecker *checker);
+
+// Returns how well the paths fit the standard,
+// from 0.0 (not at all) to 1.0 (fully),
+// or a negative number on error.
+//
+// # Safety
+//
+// `coverage` must be a pointer returned by [`osh_dir_std_checker_finish`].
+float osh_dir_std_coverage_rating(const struct OshDirStdCoverage *coverage);
+
+// Returns the JSON report of the coverage,
+// the same as the output of `osh-dir-std map`,
+// or `NULL` on error.
+// The string has to be released with [`osh_dir_std_string_free`].
+//
+// # Safety
+//
+// `coverage` must be a pointer returned by [`osh_dir_std_checker_finish`].
+char *osh_dir_std_coverage_report_json(const struct OshDirStdCoverage *coverage);
+
+// Releases a coverage.
+//
+// # Safety
+//
+// `coverage` must be `NULL` or a pointer returned by [`osh_dir_std_checker_finish`],
+// and must not be used afterwards.
+void osh_dir_std_coverage_free(struct OshDirStdCoverage *coverage);
+
+// Releases a string returned by the library.
+//
+// # Safety
+//
+// `text` must be `NULL` or a string returned by
+// [`osh_dir_std_coverage_report_json`],
+// and must not be used afterwards.
+void osh_dir_std_string_free(char *text);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* OSH_DIR_STD_H */
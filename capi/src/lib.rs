@@ -0,0 +1,399 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! A C API for [`osh_dir_std`],
+//! to embed it into C/C++, Go and other tooling
+//! without requiring a Rust toolchain on the consumer side.
+//!
+//! The header `include/osh_dir_std.h` is generated on build.
+//! Typical usage:
+//!
+//! ```c
+//! const OshDirStdStandard *std = osh_dir_std_default_standard();
+//! OshDirStdChecker *checker = osh_dir_std_checker_new(std, NULL);
+//! osh_dir_std_checker_add_path(checker, "doc/index.md");
+//! OshDirStdCoverage *coverage = osh_dir_std_checker_finish(checker);
+//! float rating = osh_dir_std_coverage_rating(coverage);
+//! char *report = osh_dir_std_coverage_report_json(coverage);
+//! osh_dir_std_string_free(report);
+//! osh_dir_std_coverage_free(coverage);
+//! ```
+//!
+//! Functions that can fail return `NULL` or a negative number,
+//! and the reason can be fetched with [`osh_dir_std_last_error`].
+//! Checkers and coverages must only be used from one thread at a time.
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, CStr, CString},
+    io,
+    path::PathBuf,
+    ptr,
+    sync::LazyLock,
+};
+
+use osh_dir_std::{
+    constants::DEFAULT_IGNORED_PATHS, data::STD_NAMES, format::DirStd, listing::DirsAdder,
+    matching::MatchPolicy, stds, Checker, Coverage, Report,
+};
+
+/// A directory standard;
+/// owned by the library, and valid for the whole runtime of the program.
+pub struct OshDirStdStandard {
+    std: &'static DirStd,
+    name: CString,
+}
+
+/// Checks paths against a standard, one at a time.
+pub struct OshDirStdChecker {
+    checker: Checker,
+    dirs_adder: DirsAdder,
+}
+
+/// Which paths fed to a checker are covered by which records of its standard.
+pub struct OshDirStdCoverage {
+    coverage: Coverage,
+}
+
+static STANDARDS: LazyLock<Vec<OshDirStdStandard>> = LazyLock::new(|| {
+    STD_NAMES
+        .iter()
+        .filter_map(|std_name| stds::by_name(std_name).ok())
+        .map(|std| OshDirStdStandard {
+            std,
+            name: CString::new(std.name).expect("Standard names contain no NUL bytes"),
+        })
+        .collect()
+});
+
+static VERSION: LazyLock<CString> = LazyLock::new(|| {
+    CString::new(osh_dir_std::VERSION).expect("The version contains no NUL bytes")
+});
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error<E: ToString>(err: &E) {
+    let msg = CString::new(err.to_string().replace('\0', "\\0"))
+        .expect("NUL bytes were replaced just now");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(msg));
+}
+
+/// Converts a C string into a Rust string,
+/// recording an error if it is `NULL` or not valid UTF-8.
+///
+/// # Safety
+///
+/// `text` must be `NULL` or a valid, NUL terminated C string.
+unsafe fn to_str<'a>(text: *const c_char, what: &str) -> Option<&'a str> {
+    if text.is_null() {
+        set_last_error(&format!("{what} must not be NULL"));
+        return None;
+    }
+    // SAFETY: Ensured by the caller
+    let c_str = unsafe { CStr::from_ptr(text) };
+    c_str
+        .to_str()
+        .map_err(|err| set_last_error(&format!("{what} is not valid UTF-8: {err}")))
+        .ok()
+}
+
+fn to_c_string(text: String) -> *mut c_char {
+    CString::new(text).map_or_else(
+        |err| {
+            set_last_error(&err);
+            ptr::null_mut()
+        },
+        CString::into_raw,
+    )
+}
+
+/// Returns the message of the last error that occurred in this thread,
+/// or `NULL` if there was none.
+/// The string is owned by the library,
+/// and valid until the next failing call in the same thread.
+#[no_mangle]
+pub extern "C" fn osh_dir_std_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |msg| msg.as_ptr())
+    })
+}
+
+/// Returns the version of the library.
+/// The string is owned by the library.
+#[no_mangle]
+pub extern "C" fn osh_dir_std_version() -> *const c_char {
+    VERSION.as_ptr()
+}
+
+/// Returns the number of known standards.
+#[no_mangle]
+pub extern "C" fn osh_dir_std_standards_count() -> usize {
+    STANDARDS.len()
+}
+
+/// Returns the known standard at the given index,
+/// or `NULL` if the index is out of range.
+#[no_mangle]
+pub extern "C" fn osh_dir_std_standard_at(index: usize) -> *const OshDirStdStandard {
+    STANDARDS.get(index).map_or_else(
+        || {
+            set_last_error(&format!("No standard at index {index}"));
+            ptr::null()
+        },
+        ptr::from_ref,
+    )
+}
+
+/// Returns the default standard.
+#[no_mangle]
+pub extern "C" fn osh_dir_std_default_standard() -> *const OshDirStdStandard {
+    STANDARDS
+        .iter()
+        .find(|standard| standard.std.name == osh_dir_std::DEFAULT_STD_NAME)
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Returns the standard with the given name,
+/// or `NULL` if there is none.
+///
+/// # Safety
+///
+/// `name` must be a valid, NUL terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_standard_by_name(
+    name: *const c_char,
+) -> *const OshDirStdStandard {
+    // SAFETY: Ensured by the caller
+    let Some(std_name) = (unsafe { to_str(name, "name") }) else {
+        return ptr::null();
+    };
+    match stds::by_name(std_name) {
+        Ok(std) => STANDARDS
+            .iter()
+            .find(|standard| standard.std.name == std.name)
+            .map_or(ptr::null(), ptr::from_ref),
+        Err(err) => {
+            set_last_error(&err);
+            ptr::null()
+        }
+    }
+}
+
+/// Returns the name of the standard.
+/// The string is owned by the library.
+///
+/// # Safety
+///
+/// `standard` must be a pointer returned by one of the `osh_dir_std_standard*` functions.
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_standard_name(
+    standard: *const OshDirStdStandard,
+) -> *const c_char {
+    // SAFETY: Ensured by the caller
+    unsafe { standard.as_ref() }.map_or(ptr::null(), |standard_ref| standard_ref.name.as_ptr())
+}
+
+/// Creates a checker for the standard.
+///
+/// Paths matching `ignored_paths_regex` are ignored;
+/// if it is `NULL`, hidden files and dirs are ignored.
+/// Returns `NULL` on error.
+/// The checker has to be released with either
+/// [`osh_dir_std_checker_finish`] or [`osh_dir_std_checker_free`].
+///
+/// # Safety
+///
+/// `standard` must be a pointer returned by one of the `osh_dir_std_standard*` functions,
+/// and `ignored_paths_regex` must be `NULL` or a valid, NUL terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_checker_new(
+    standard: *const OshDirStdStandard,
+    ignored_paths_regex: *const c_char,
+) -> *mut OshDirStdChecker {
+    // SAFETY: Ensured by the caller
+    let Some(standard_ref) = (unsafe { standard.as_ref() }) else {
+        set_last_error(&"standard must not be NULL");
+        return ptr::null_mut();
+    };
+    let ignored_paths = if ignored_paths_regex.is_null() {
+        DEFAULT_IGNORED_PATHS.clone()
+    } else {
+        // SAFETY: Ensured by the caller
+        let Some(pattern) = (unsafe { to_str(ignored_paths_regex, "ignored_paths_regex") }) else {
+            return ptr::null_mut();
+        };
+        match regex::Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(err) => {
+                set_last_error(&err);
+                return ptr::null_mut();
+            }
+        }
+    };
+    match Checker::new(standard_ref.std, &ignored_paths, MatchPolicy::default()) {
+        Ok(checker) => Box::into_raw(Box::new(OshDirStdChecker {
+            checker,
+            dirs_adder: DirsAdder::new(),
+        })),
+        Err(err) => {
+            set_last_error(&err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Feeds a single path, relative to the project root, to the checker.
+/// Its ancestor dirs are added automatically,
+/// and paths that were already fed are skipped.
+/// Returns 0 on success, and -1 on error.
+///
+/// # Safety
+///
+/// `checker` must be a pointer returned by [`osh_dir_std_checker_new`],
+/// and `path` a valid, NUL terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_checker_add_path(
+    checker: *mut OshDirStdChecker,
+    path: *const c_char,
+) -> c_int {
+    // SAFETY: Ensured by the caller
+    let Some(checker_mut) = (unsafe { checker.as_mut() }) else {
+        set_last_error(&"checker must not be NULL");
+        return -1;
+    };
+    // SAFETY: Ensured by the caller
+    let Some(path_str) = (unsafe { to_str(path, "path") }) else {
+        return -1;
+    };
+    for dir_or_file_res in checker_mut
+        .dirs_adder
+        .add(Ok::<_, io::Error>(PathBuf::from(path_str)))
+    {
+        match dir_or_file_res {
+            Ok(dir_or_file) => {
+                checker_mut.checker.cover(&dir_or_file);
+            }
+            Err(err) => {
+                set_last_error(&err);
+                return -1;
+            }
+        }
+    }
+    0
+}
+
+/// Releases the checker, and returns the coverage of all the paths fed to it.
+/// The coverage has to be released with [`osh_dir_std_coverage_free`].
+///
+/// # Safety
+///
+/// `checker` must be a pointer returned by [`osh_dir_std_checker_new`],
+/// and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_checker_finish(
+    checker: *mut OshDirStdChecker,
+) -> *mut OshDirStdCoverage {
+    if checker.is_null() {
+        set_last_error(&"checker must not be NULL");
+        return ptr::null_mut();
+    }
+    // SAFETY: Ensured by the caller
+    let checker_box = unsafe { Box::from_raw(checker) };
+    Box::into_raw(Box::new(OshDirStdCoverage {
+        coverage: checker_box.checker.coverage(),
+    }))
+}
+
+/// Releases a checker without evaluating it.
+///
+/// # Safety
+///
+/// `checker` must be `NULL` or a pointer returned by [`osh_dir_std_checker_new`],
+/// and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_checker_free(checker: *mut OshDirStdChecker) {
+    if !checker.is_null() {
+        // SAFETY: Ensured by the caller
+        drop(unsafe { Box::from_raw(checker) });
+    }
+}
+
+/// Returns how well the paths fit the standard,
+/// from 0.0 (not at all) to 1.0 (fully),
+/// or a negative number on error.
+///
+/// # Safety
+///
+/// `coverage` must be a pointer returned by [`osh_dir_std_checker_finish`].
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_coverage_rating(coverage: *const OshDirStdCoverage) -> f32 {
+    // SAFETY: Ensured by the caller
+    unsafe { coverage.as_ref() }.map_or_else(
+        || {
+            set_last_error(&"coverage must not be NULL");
+            -1.0
+        },
+        |coverage_ref| coverage_ref.coverage.rate(),
+    )
+}
+
+/// Returns the JSON report of the coverage,
+/// the same as the output of `osh-dir-std map`,
+/// or `NULL` on error.
+/// The string has to be released with [`osh_dir_std_string_free`].
+///
+/// # Safety
+///
+/// `coverage` must be a pointer returned by [`osh_dir_std_checker_finish`].
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_coverage_report_json(
+    coverage: *const OshDirStdCoverage,
+) -> *mut c_char {
+    // SAFETY: Ensured by the caller
+    let Some(coverage_ref) = (unsafe { coverage.as_ref() }) else {
+        set_last_error(&"coverage must not be NULL");
+        return ptr::null_mut();
+    };
+    let report = Report::new(std::slice::from_ref(&coverage_ref.coverage));
+    match serde_json::to_string(&report) {
+        Ok(json) => to_c_string(json),
+        Err(err) => {
+            set_last_error(&err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a coverage.
+///
+/// # Safety
+///
+/// `coverage` must be `NULL` or a pointer returned by [`osh_dir_std_checker_finish`],
+/// and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_coverage_free(coverage: *mut OshDirStdCoverage) {
+    if !coverage.is_null() {
+        // SAFETY: Ensured by the caller
+        drop(unsafe { Box::from_raw(coverage) });
+    }
+}
+
+/// Releases a string returned by the library.
+///
+/// # Safety
+///
+/// `text` must be `NULL` or a string returned by
+/// [`osh_dir_std_coverage_report_json`],
+/// and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn osh_dir_std_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        // SAFETY: Ensured by the caller
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+// The dependencies of the library part are not all used here
+#![allow(unused_crate_dependencies)]
+
+use std::ffi::{CStr, CString};
+
+use osh_dir_std_c::{
+    osh_dir_std_checker_add_path, osh_dir_std_checker_finish, osh_dir_std_checker_new,
+    osh_dir_std_coverage_free, osh_dir_std_coverage_rating, osh_dir_std_coverage_report_json,
+    osh_dir_std_last_error, osh_dir_std_standard_by_name, osh_dir_std_standard_name,
+    osh_dir_std_string_free,
+};
+
+#[test]
+fn check_paths_through_c_api() {
+    let std_name = CString::new("unixish").unwrap();
+    let unknown_name = CString::new("nonexistent").unwrap();
+    unsafe {
+        assert!(osh_dir_std_standard_by_name(unknown_name.as_ptr()).is_null());
+        assert!(!osh_dir_std_last_error().is_null());
+
+        let std = osh_dir_std_standard_by_name(std_name.as_ptr());
+        assert!(!std.is_null());
+        assert_eq!(
+            CStr::from_ptr(osh_dir_std_standard_name(std)),
+            std_name.as_c_str()
+        );
+
+        let checker = osh_dir_std_checker_new(std, std::ptr::null());
+        assert!(!checker.is_null());
+        for path in ["README.md", "LICENSE.txt", "doc/index.md"] {
+            let c_path = CString::new(path).unwrap();
+            assert_eq!(osh_dir_std_checker_add_path(checker, c_path.as_ptr()), 0);
+        }
+        let coverage = osh_dir_std_checker_finish(checker);
+        assert!(osh_dir_std_coverage_rating(coverage) > 0.0);
+
+        let report = osh_dir_std_coverage_report_json(coverage);
+        let report_json = CStr::from_ptr(report).to_str().unwrap().to_owned();
+        osh_dir_std_string_free(report);
+        osh_dir_std_coverage_free(coverage);
+        assert!(report_json.contains(r#""name":"unixish""#));
+    }
+}
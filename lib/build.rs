@@ -11,6 +11,8 @@ use std::{
     process,
 };
 
+// Only the parsing part of this module is used here
+#[allow(dead_code)]
 #[path = "src/format.rs"]
 mod format;
 
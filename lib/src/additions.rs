@@ -0,0 +1,293 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt::Display,
+    hash::BuildHasher,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::{
+    error::SUGGESTION_MIN_SIMILARITY,
+    format::{DirStd, Rec},
+    matching::MatchPolicy,
+    Checker, Error, Status,
+};
+
+/// Why adding a path violates the standard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Problem {
+    /// The path matches no record of the standard,
+    /// and neither does its parent dir.
+    OutOfStandard,
+    /// The path matches a record that is not supposed to be tracked
+    /// (e.g. build output).
+    Untracked { record: String },
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfStandard => write!(f, "not part of the standard"),
+            Self::Untracked { record } => {
+                write!(f, "matches '{record}', which should not be tracked")
+            }
+        }
+    }
+}
+
+/// A newly added path that violates the standard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub path: PathBuf,
+    pub problem: Problem,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.problem)
+    }
+}
+
+/// Checks only the paths that are about to be added to a project
+/// (e.g. the staged ones in a VCS),
+/// leaving the deviations of the existing paths alone.
+///
+/// `existing` are the paths already in the project,
+/// including all their ancestor dirs.
+/// Of the `added` paths and their ancestors, only those not `existing`
+/// are checked; out-of-standard paths are only reported
+/// if their parent dir is part of the standard,
+/// so content added to an already deviating dir is accepted,
+/// and a new deviating dir is reported only once.
+///
+/// # Errors
+///
+/// If the standard can not be used.
+pub fn check_additions<S, I>(
+    std: &'static DirStd,
+    ignored_paths: &Regex,
+    policy: MatchPolicy,
+    existing: &HashSet<PathBuf, S>,
+    added: I,
+) -> Result<Vec<Violation>, Error>
+where
+    S: BuildHasher,
+    I: IntoIterator<Item = PathBuf>,
+{
+    let mut checker = Checker::new(std, ignored_paths, policy)?;
+    let new_paths = added
+        .into_iter()
+        .flat_map(|path| {
+            path.ancestors()
+                .filter(|ancestor| !ancestor.as_os_str().is_empty())
+                .map(Path::to_path_buf)
+                .collect::<Vec<_>>()
+        })
+        .filter(|path| !existing.contains(path))
+        .collect::<BTreeSet<_>>();
+
+    let mut violations = vec![];
+    for path in new_paths {
+        let mapping = checker.cover(&Rc::new(path.clone()));
+        if let Some(untracked) = mapping.records.iter().find(|rec| !rec.tracked) {
+            violations.push(Violation {
+                path,
+                problem: Problem::Untracked {
+                    record: untracked.path.to_owned(),
+                },
+            });
+            continue;
+        }
+        if mapping.status != Status::Out {
+            continue;
+        }
+        let parent_out = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .is_some_and(|parent| {
+                checker.classify(&Rc::new(parent.to_path_buf())).status == Status::Out
+            });
+        if !parent_out {
+            violations.push(Violation {
+                path,
+                problem: Problem::OutOfStandard,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+/// A path added in a change, that does not fit the standard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Misfit {
+    pub path: PathBuf,
+    pub status: Status,
+    /// The path of the record this path most likely was meant for,
+    /// judging by the similarity of the names.
+    pub suggestion: Option<String>,
+}
+
+impl Display for Misfit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: not part of the standard", self.path.display())?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "; maybe it belongs to '{suggestion}'?")?;
+        }
+        Ok(())
+    }
+}
+
+/// The names a record matches, as far as they are known literally.
+fn record_names<'a>(rec: &'a Rec<'a>) -> Vec<&'a str> {
+    rec.variations.as_ref().map_or_else(
+        || {
+            let name = rec.path.trim_end_matches('/');
+            let last = name.rsplit('/').next().unwrap_or(name);
+            // Skip wildcard records like "mod/*/"
+            if last.contains('*') {
+                vec![]
+            } else {
+                vec![last]
+            }
+        },
+        Clone::clone,
+    )
+}
+
+/// Finds the (tracked) record whose name is most similar
+/// to one of the components of the given path.
+#[must_use]
+pub fn suggest_record(std: &'static DirStd, path: &Path) -> Option<&'static Rec<'static>> {
+    let components = path
+        .components()
+        .map(|comp| comp.as_os_str().to_string_lossy().to_lowercase())
+        .collect::<Vec<_>>();
+    let mut best: Option<(f64, &'static Rec<'static>)> = None;
+    for rec in std.records.iter().filter(|rec| rec.tracked) {
+        for name in record_names(rec) {
+            let name_lower = name.to_lowercase();
+            for comp in &components {
+                let score = strsim::jaro_winkler(comp, &name_lower);
+                // NOTE On equal scores, the first record wins.
+                if score >= SUGGESTION_MIN_SIMILARITY
+                    && best.is_none_or(|(best_score, _)| score > best_score)
+                {
+                    best = Some((score, rec));
+                }
+            }
+        }
+    }
+    best.map(|(_score, rec)| rec)
+}
+
+/// Checks the paths added in a change (e.g. a PR),
+/// within the context of the whole project after the change,
+/// so module boundaries resolve as in a full check.
+///
+/// `dirs_and_files` is the complete listing after the change,
+/// including ancestor dirs (see [`crate::ListingSource::dirs_and_files`]).
+/// Returns the `added` paths that do not fit the standard.
+///
+/// # Errors
+///
+/// If the standard can not be used, or reading the listing failed.
+pub fn check_changes<T, E, S>(
+    std: &'static DirStd,
+    ignored_paths: &Regex,
+    policy: MatchPolicy,
+    dirs_and_files: T,
+    added: &HashSet<PathBuf, S>,
+) -> Result<Vec<Misfit>, Error>
+where
+    T: Iterator<Item = Result<Rc<PathBuf>, E>>,
+    Error: From<E>,
+    S: BuildHasher,
+{
+    let mut checker = Checker::new(std, ignored_paths, policy)?;
+    let mut misfits = vec![];
+    for dir_or_file_res in dirs_and_files {
+        let dir_or_file = dir_or_file_res?;
+        let mapping = checker.cover(&dir_or_file);
+        if mapping.status == Status::Out && added.contains(dir_or_file.as_ref()) {
+            misfits.push(Misfit {
+                path: dir_or_file.as_ref().clone(),
+                status: mapping.status,
+                suggestion: suggest_record(std, &dir_or_file).map(|rec| rec.path.to_owned()),
+            });
+        }
+    }
+    misfits.sort_by(|misfit_a, misfit_b| misfit_a.path.cmp(&misfit_b.path));
+    Ok(misfits)
+}
+
+/// Parses the added, copied and renamed paths from a `git diff --name-status` listing.
+///
+/// It has one change per line, starting with a status letter
+/// (`A`, `M`, `D`, `R100`, ...), followed by tab separated paths,
+/// the last one being the new one.
+/// Paths that git quotes (e.g. those containing non-ASCII characters)
+/// are not unquoted; use [`parse_name_status_nul`] for those.
+///
+/// # Errors
+///
+/// If reading failed.
+pub fn parse_name_status<R: BufRead>(reader: R) -> io::Result<Vec<PathBuf>> {
+    let mut added = vec![];
+    for line_res in reader.lines() {
+        let line = line_res?;
+        let mut parts = line.split('\t');
+        let status = parts.next().unwrap_or_default();
+        if matches!(status.chars().next(), Some('A' | 'C' | 'R')) {
+            if let Some(path) = parts.next_back() {
+                added.push(PathBuf::from(path));
+            }
+        }
+    }
+    Ok(added)
+}
+
+/// Parses the added, copied and renamed paths
+/// from a NUL-separated `git diff --name-status -z` listing.
+///
+/// In it, each status letter and each path is terminated by a NUL character,
+/// with renames and copies being followed by both the old and the new path.
+/// As git does not quote paths in this format,
+/// it also works for paths containing special or non-ASCII characters.
+///
+/// # Errors
+///
+/// If reading failed.
+pub fn parse_name_status_nul<R: BufRead>(reader: R) -> io::Result<Vec<PathBuf>> {
+    let mut added = vec![];
+    let mut fields = reader.split(b'\0');
+    while let Some(status_res) = fields.next() {
+        let status = status_res?;
+        let Some(status_letter) = status.first() else {
+            continue;
+        };
+        let num_paths = if matches!(status_letter, b'C' | b'R') {
+            2
+        } else {
+            1
+        };
+        let mut last_path = None;
+        for _ in 0..num_paths {
+            last_path = fields.next().transpose()?;
+        }
+        if matches!(status_letter, b'A' | b'C' | b'R') {
+            if let Some(path) = last_path {
+                added.push(PathBuf::from(String::from_utf8_lossy(&path).as_ref()));
+            }
+        }
+    }
+    Ok(added)
+}
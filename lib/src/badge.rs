@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ci::escape_xml, Rating};
+
+pub const DEFAULT_LABEL: &str = "dir-std";
+
+pub const COLOR_GOOD: &str = "#4c1";
+pub const COLOR_FAIR: &str = "#dfb317";
+pub const COLOR_POOR: &str = "#e05d44";
+
+/// From which rating factor on a badge gets which color.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BadgeThresholds {
+    /// From this factor on, the badge is green.
+    pub good: f32,
+    /// From this factor on, the badge is yellow;
+    /// below, it is red.
+    pub fair: f32,
+}
+
+impl Default for BadgeThresholds {
+    fn default() -> Self {
+        Self {
+            good: 0.8,
+            fair: 0.5,
+        }
+    }
+}
+
+impl BadgeThresholds {
+    /// Returns the color for a rating factor.
+    #[must_use]
+    pub fn color(self, factor: f32) -> &'static str {
+        if factor >= self.good {
+            COLOR_GOOD
+        } else if factor >= self.fair {
+            COLOR_FAIR
+        } else {
+            COLOR_POOR
+        }
+    }
+}
+
+/// Roughly estimates the width of a text in pixels,
+/// as rendered in 11px Verdana, the font used for badges.
+fn text_width(text: &str) -> u32 {
+    text.chars()
+        .map(|chr| match chr {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '!' | '|' | '\'' | ' ' => 4,
+            'f' | 'r' | 't' | '(' | ')' | '[' | ']' | '-' | '/' => 5,
+            'm' | 'w' | '%' | 'M' | 'W' => 10,
+            chr if chr.is_uppercase() => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+/// Renders a self-contained, shields.io style SVG badge.
+#[must_use]
+pub fn render(label: &str, message: &str, color: &str) -> String {
+    const PADDING: u32 = 10;
+    let label_width = text_width(label) + PADDING;
+    let message_width = text_width(message) + PADDING;
+    let width = label_width + message_width;
+    let label_x = label_width / 2;
+    let message_x = label_width + message_width / 2;
+    let label_esc = escape_xml(label);
+    let message_esc = escape_xml(message);
+    let color_esc = escape_xml(color);
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label_esc}: {message_esc}">
+  <title>{label_esc}: {message_esc}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r">
+    <rect width="{width}" height="20" rx="3" fill="#fff"/>
+  </clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{color_esc}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label_esc}</text>
+    <text x="{label_x}" y="14">{label_esc}</text>
+    <text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message_esc}</text>
+    <text x="{message_x}" y="14">{message_esc}</text>
+  </g>
+</svg>
+"##
+    )
+}
+
+/// Renders a badge like "dir-std | unixish 87%",
+/// colored according to the thresholds.
+#[must_use]
+pub fn rating_badge(label: &str, rating: &Rating, thresholds: BadgeThresholds) -> String {
+    let percent = (rating.factor * 100.0).round();
+    render(
+        label,
+        &format!("{} {percent}%", rating.name),
+        thresholds.color(rating.factor),
+    )
+}
@@ -0,0 +1,596 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{rules::Finding, tags::record_tags, Coverage, VERSION};
+
+/// How severe a deviation from the standard is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Minor,
+    Major,
+}
+
+impl Severity {
+    /// The name of this severity in GitLab Code Quality reports.
+    #[must_use]
+    pub const fn gitlab(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        }
+    }
+
+    /// The name of the GitHub workflow command for this severity.
+    #[must_use]
+    pub const fn github(self) -> &'static str {
+        match self {
+            Self::Info => "notice",
+            Self::Minor => "warning",
+            Self::Major => "error",
+        }
+    }
+
+    /// The SARIF result level for this severity.
+    #[must_use]
+    pub const fn sarif(self) -> &'static str {
+        match self {
+            Self::Info => "note",
+            Self::Minor => "warning",
+            Self::Major => "error",
+        }
+    }
+}
+
+/// What is wrong with a checked record or path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Problem {
+    /// A normative record of the standard matched no path.
+    MissingNormativeRecord,
+    /// The path matches no record of the standard.
+    OutOfStandard,
+    /// The path matches a record that is not supposed to be tracked.
+    Untracked { record: String },
+    /// The file is not of any of the types expected by the record
+    /// it matches or is below, see [`Coverage::misplaced`].
+    Misplaced {
+        record: String,
+        expected: Vec<String>,
+    },
+    /// A house rule is violated, see [`crate::rules`].
+    Rule {
+        rule: String,
+        severity: Severity,
+        message: String,
+    },
+}
+
+impl Problem {
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        match self {
+            Self::MissingNormativeRecord | Self::OutOfStandard | Self::Misplaced { .. } => {
+                Severity::Minor
+            }
+            Self::Untracked { .. } => Severity::Major,
+            Self::Rule { severity, .. } => *severity,
+        }
+    }
+
+    /// A short, machine-readable name of this kind of problem;
+    /// the name of the rule for [`Self::Rule`].
+    #[must_use]
+    pub const fn name(&self) -> &str {
+        match self {
+            Self::MissingNormativeRecord => "missing-normative-record",
+            Self::OutOfStandard => "out-of-standard",
+            Self::Untracked { .. } => "untracked",
+            Self::Misplaced { .. } => "misplaced",
+            Self::Rule { rule, .. } => rule.as_str(),
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingNormativeRecord => write!(f, "normative record matches no path"),
+            Self::OutOfStandard => write!(f, "path is not part of the standard"),
+            Self::Untracked { record } => {
+                write!(f, "path matches '{record}', which should not be tracked")
+            }
+            Self::Misplaced { record, expected } => write!(
+                f,
+                "file is below '{record}', which expects files of type(s) {}",
+                expected.join(", ")
+            ),
+            Self::Rule { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+/// What a [`Check`] was about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    /// Whether a normative record matches at least one path.
+    NormativeRecord,
+    /// Whether a path fits the standard.
+    Path,
+    /// A violation of a house rule.
+    Rule,
+}
+
+/// A single check of a coverage, passed or failed,
+/// for example one test case in a `JUnit` report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    /// The name of the standard
+    pub std: &'static str,
+    pub kind: CheckKind,
+    /// The path of the record or the checked path,
+    /// relative to the project root.
+    pub path: PathBuf,
+    /// The tags of the record the check is about, sorted;
+    /// empty for paths that are out of the standard.
+    pub tags: Vec<&'static str>,
+    /// `None` if the check passed.
+    pub problem: Option<Problem>,
+}
+
+impl Check {
+    /// The human-readable description of the problem, if any.
+    #[must_use]
+    pub fn message(&self) -> Option<String> {
+        self.problem.as_ref().map(|problem| {
+            format!(
+                "{} ({}, standard '{}')",
+                problem,
+                self.path.display(),
+                self.std
+            )
+        })
+    }
+}
+
+fn collect_checks(coverage: &Coverage, prefix: &Path, checks: &mut Vec<Check>) {
+    let mut normative = coverage
+        .std
+        .records
+        .iter()
+        .filter(|rec| rec.normative)
+        .map(|rec| {
+            let matched = coverage
+                .r#in
+                .get(rec)
+                .is_some_and(|paths| !paths.is_empty());
+            Check {
+                std: coverage.std.name,
+                kind: CheckKind::NormativeRecord,
+                path: prefix.join(rec.path),
+                tags: record_tags(rec),
+                problem: (!matched).then_some(Problem::MissingNormativeRecord),
+            }
+        })
+        .collect::<Vec<_>>();
+    normative.sort_by(|check_a, check_b| check_a.path.cmp(&check_b.path));
+    checks.extend(normative);
+
+    let mut paths = coverage
+        .out
+        .iter()
+        .map(|path| (path.as_ref(), vec![], Problem::OutOfStandard))
+        .chain(
+            coverage
+                .r#in
+                .iter()
+                .filter(|(rec, _paths)| !rec.tracked)
+                .flat_map(|(rec, paths)| {
+                    paths.iter().map(|path| {
+                        (
+                            path.as_ref(),
+                            record_tags(rec),
+                            Problem::Untracked {
+                                record: rec.path.to_owned(),
+                            },
+                        )
+                    })
+                }),
+        )
+        .chain(coverage.misplaced.iter().flat_map(|(rec, paths)| {
+            paths.iter().map(|path| {
+                (
+                    path.as_ref(),
+                    record_tags(rec),
+                    Problem::Misplaced {
+                        record: rec.path.to_owned(),
+                        expected: rec
+                            .file_types
+                            .iter()
+                            .flatten()
+                            .map(|file_type| (*file_type).to_owned())
+                            .collect(),
+                    },
+                )
+            })
+        }))
+        .map(|(path, tags, problem)| Check {
+            std: coverage.std.name,
+            kind: CheckKind::Path,
+            path: prefix.join(path),
+            tags,
+            problem: Some(problem),
+        })
+        .collect::<Vec<_>>();
+    paths.sort_by(|check_a, check_b| check_a.path.cmp(&check_b.path));
+    checks.extend(paths);
+
+    let modules = coverage.modules.iter().collect::<BTreeMap<_, _>>();
+    for (mod_path, mod_coverage) in modules {
+        collect_checks(mod_coverage, &prefix.join(mod_path), checks);
+    }
+}
+
+/// Derives the checks for CI reports from a coverage:
+/// One per normative record,
+/// and one per path that is out of the standard,
+/// should not be tracked or is misplaced,
+/// including those of all modules.
+#[must_use]
+pub fn checks(coverage: &Coverage) -> Vec<Check> {
+    let mut checks = vec![];
+    collect_checks(coverage, Path::new(""), &mut checks);
+    checks
+}
+
+/// Converts the findings of house rules into failed checks.
+#[must_use]
+pub fn rule_checks(std: &'static str, findings: Vec<Finding>) -> Vec<Check> {
+    findings
+        .into_iter()
+        .map(|finding| Check {
+            std,
+            kind: CheckKind::Rule,
+            path: finding.path,
+            tags: vec![],
+            problem: Some(Problem::Rule {
+                rule: finding.rule,
+                severity: finding.severity,
+                message: finding.message,
+            }),
+        })
+        .collect()
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Writes the checks as a `JUnit` XML report,
+/// with one test suite per standard.
+///
+/// # Errors
+///
+/// If writing failed.
+pub fn write_junit<W: Write>(checks: &[Check], mut wtr: W) -> io::Result<()> {
+    let mut suites: BTreeMap<&str, Vec<&Check>> = BTreeMap::new();
+    for check in checks {
+        suites.entry(check.std).or_default().push(check);
+    }
+    let num_failures = checks
+        .iter()
+        .filter(|check| check.problem.is_some())
+        .count();
+    writeln!(wtr, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        wtr,
+        r#"<testsuites name="osh-dir-std" tests="{}" failures="{num_failures}">"#,
+        checks.len()
+    )?;
+    for (std, suite) in suites {
+        let suite_failures = suite.iter().filter(|check| check.problem.is_some()).count();
+        writeln!(
+            wtr,
+            r#"  <testsuite name="{}" tests="{}" failures="{suite_failures}">"#,
+            escape_xml(std),
+            suite.len()
+        )?;
+        for check in suite {
+            let class_name = match check.kind {
+                CheckKind::NormativeRecord => "normative-record",
+                CheckKind::Path => "path",
+                CheckKind::Rule => "rule",
+            };
+            let name = escape_xml(&check.path.to_string_lossy());
+            write!(
+                wtr,
+                r#"    <testcase classname="{}.{class_name}" name="{name}" file="{name}""#,
+                escape_xml(std)
+            )?;
+            if let (Some(problem), Some(message)) = (&check.problem, check.message()) {
+                writeln!(wtr, ">")?;
+                writeln!(
+                    wtr,
+                    r#"      <failure type="{}" message="{}"/>"#,
+                    problem.name(),
+                    escape_xml(&message)
+                )?;
+                writeln!(wtr, "    </testcase>")?;
+            } else {
+                writeln!(wtr, "/>")?;
+            }
+        }
+        writeln!(wtr, "  </testsuite>")?;
+    }
+    writeln!(wtr, "</testsuites>")
+}
+
+/// A stable fingerprint (64bit FNV-1a), as required by GitLab
+/// to track issues across pipelines.
+fn fingerprint(parts: &[&str]) -> String {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+    let mut hash = FNV_OFFSET;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+#[derive(Serialize)]
+struct GitLabLines {
+    begin: u32,
+}
+
+#[derive(Serialize)]
+struct GitLabLocation {
+    path: String,
+    lines: GitLabLines,
+}
+
+#[derive(Serialize)]
+struct GitLabIssue<'a> {
+    description: String,
+    check_name: &'a str,
+    fingerprint: String,
+    severity: &'static str,
+    location: GitLabLocation,
+}
+
+/// Writes the failed checks as a GitLab Code Quality report.
+///
+/// # Errors
+///
+/// If serializing or writing failed.
+pub fn write_gitlab<W: Write>(checks: &[Check], mut wtr: W) -> io::Result<()> {
+    let issues = checks
+        .iter()
+        .filter_map(|check| {
+            let problem = check.problem.as_ref()?;
+            let path = check.path.to_string_lossy().into_owned();
+            Some(GitLabIssue {
+                description: check.message()?,
+                check_name: problem.name(),
+                fingerprint: fingerprint(&[check.std, problem.name(), &path]),
+                severity: problem.severity().gitlab(),
+                location: GitLabLocation {
+                    path,
+                    lines: GitLabLines { begin: 1 },
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_writer_pretty(&mut wtr, &issues).map_err(io::Error::other)?;
+    writeln!(wtr)
+}
+
+/// Escapes the data part of a GitHub workflow command.
+fn escape_github_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property value of a GitHub workflow command.
+fn escape_github_property(text: &str) -> String {
+    escape_github_data(text)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Writes the failed checks as GitHub workflow commands
+/// (`::warning file=...::...`),
+/// which show up as annotations in pull requests.
+///
+/// # Errors
+///
+/// If writing failed.
+pub fn write_github<W: Write>(checks: &[Check], mut wtr: W) -> io::Result<()> {
+    for check in checks {
+        if let (Some(problem), Some(message)) = (&check.problem, check.message()) {
+            writeln!(
+                wtr,
+                "::{} file={},title={}::{}",
+                problem.severity().github(),
+                escape_github_property(&check.path.to_string_lossy()),
+                escape_github_property(&format!("osh-dir-std: {}", problem.name())),
+                escape_github_data(&message)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the failed checks as plain text,
+/// one line per problem.
+///
+/// # Errors
+///
+/// If writing failed.
+pub fn write_text<W: Write>(checks: &[Check], mut wtr: W) -> io::Result<()> {
+    for check in checks {
+        if let (Some(problem), Some(message)) = (&check.problem, check.message()) {
+            writeln!(
+                wtr,
+                "{}: {message} [{}]",
+                problem.severity().sarif(),
+                problem.name()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct SarifText {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: String,
+    short_description: SarifText,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    level: &'static str,
+    message: SarifText,
+    locations: Vec<SarifLocation>,
+    partial_fingerprints: BTreeMap<&'static str, String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    information_uri: &'static str,
+    version: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+/// Writes the failed checks as a SARIF 2.1.0 log,
+/// as understood by GitHub code scanning and many IDEs.
+///
+/// # Errors
+///
+/// If serializing or writing failed.
+pub fn write_sarif<W: Write>(checks: &[Check], mut wtr: W) -> io::Result<()> {
+    let mut rules = BTreeMap::new();
+    let results = checks
+        .iter()
+        .filter_map(|check| {
+            let problem = check.problem.as_ref()?;
+            let path = check.path.to_string_lossy().into_owned();
+            rules
+                .entry(problem.name().to_owned())
+                .or_insert_with(|| match problem {
+                    Problem::Rule { message, .. } => message.clone(),
+                    Problem::MissingNormativeRecord
+                    | Problem::OutOfStandard
+                    | Problem::Untracked { .. }
+                    | Problem::Misplaced { .. } => problem.name().replace('-', " "),
+                });
+            Some(SarifResult {
+                rule_id: problem.name().to_owned(),
+                level: problem.severity().sarif(),
+                message: SarifText {
+                    text: check.message()?,
+                },
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: SarifArtifactLocation { uri: path.clone() },
+                    },
+                }],
+                partial_fingerprints: BTreeMap::from([(
+                    "oshDirStd/v1",
+                    fingerprint(&[check.std, problem.name(), &path]),
+                )]),
+            })
+        })
+        .collect::<Vec<_>>();
+    let sarif_rules = rules
+        .into_iter()
+        .map(|(id, description)| SarifRule {
+            id,
+            short_description: SarifText { text: description },
+        })
+        .collect::<Vec<_>>();
+    let log = SarifLog {
+        schema: "https://json.schemastore.org/sarif-2.1.0.json",
+        version: "2.1.0",
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: "osh-dir-std",
+                    information_uri: "https://github.com/hoijui/osh-dir-std-rs",
+                    version: VERSION,
+                    rules: sarif_rules,
+                },
+            },
+            results,
+        }],
+    };
+    serde_json::to_writer_pretty(&mut wtr, &log).map_err(io::Error::other)?;
+    writeln!(wtr)
+}
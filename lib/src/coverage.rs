@@ -3,24 +3,24 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     hash::{DefaultHasher, Hash, Hasher},
     io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
 };
-use thiserror::Error;
 use tracing::trace;
 
 use crate::{
-    best_fit,
-    data::STDS,
-    evaluation::{BestFitError, RatingCont},
-    stds::Standards,
+    evaluation::{Ranking, RatingCont},
+    file_types,
+    format::Rec,
+    matching::MatchPolicy,
+    stds::{self, Standards},
     tree::{self, RNode},
-    Rating, DEFAULT_STD_NAME,
+    Error, Rating, DEFAULT_STD_NAME,
 };
 
 use super::format::DirStd;
@@ -32,11 +32,37 @@ pub struct Checker {
     /// the coverage in creation
     coverage: Coverage,
     ignored_paths: Regex,
-    arbitrary_content_rgxs: Option<Vec<Regex>>,
-    generated_content_rgxs: Option<Vec<Regex>>,
-    module_rgxs: Option<Vec<Regex>>,
-    modules: HashMap<PathBuf, Checker>,
-    records_tree: Option<(RNode<'static>, Vec<RNode<'static>>)>,
+    policy: MatchPolicy,
+    /// Shared with the checkers of the modules
+    matchers: Rc<Matchers>,
+    modules: HashMap<PathBuf, Self>,
+}
+
+/// The regexes derived from a standard,
+/// assembled according to a matching policy.
+#[derive(Debug)]
+struct Matchers {
+    arbitrary_content_rgxs: Vec<Regex>,
+    generated_content_rgxs: Vec<Regex>,
+    module_rgxs: Vec<Regex>,
+    /// The regexes matching the paths whose file types are expected
+    /// by a record, together with that record
+    file_type_rgxs: Vec<(Regex, &'static Rec<'static>)>,
+    records_tree: (RNode<'static>, Vec<RNode<'static>>),
+}
+
+impl Matchers {
+    fn new(std: &'static DirStd, policy: MatchPolicy) -> Result<Self, Error> {
+        let records_tree = tree::create(std, policy)?;
+        let tree_recs = &records_tree.1;
+        Ok(Self {
+            arbitrary_content_rgxs: create_arbitrary_content_rgxs(std, tree_recs)?,
+            generated_content_rgxs: create_generated_content_rgxs(std, tree_recs)?,
+            module_rgxs: create_module_rgxs(std, tree_recs)?,
+            file_type_rgxs: create_file_type_rgxs(std, tree_recs)?,
+            records_tree,
+        })
+    }
 }
 
 /// Indicates which relative paths of all dirs and files in a project
@@ -76,16 +102,372 @@ pub struct Coverage {
     /// The viable paths in the input dir that did not match any record
     /// of the checked standard.
     pub out: Vec<Rc<PathBuf>>,
+    /// The paths that are part of the standard,
+    /// but are not of any of the file types expected
+    /// by the most specific record they match or are below,
+    /// grouped by that record.
+    /// For example, an STL file in the documentation dir.
+    /// These paths are also contained in `in` or `arbitrary_content`,
+    /// and do not lower the rating.
+    pub misplaced: HashMap<&'static Rec<'static>, Vec<Rc<PathBuf>>>,
     /// The coverages for the modules directly included in the root listing;
     /// sub-modules (modules of modules) are contained in the sub coverage.
     /// The path used as key here, is the path of the module directory -
     /// modules always are assumed to be rooted in one directory each.
     /// We also assume, that the name of that directory
     /// is the (machine-readable version of) the modules name.
-    pub modules: HashMap<PathBuf, Coverage>,
+    pub modules: HashMap<PathBuf, Self>,
+}
+
+/// The category a single dir or file falls into,
+/// when checked against a specific dir standard.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// Matches one or more records of the standard.
+    In,
+    /// Matches one or more records of the standard,
+    /// at least one of which is a module (root dir).
+    Module,
+    /// Matches no record of the standard.
+    #[default]
+    Out,
+    /// Matches the ignored paths.
+    Ignored,
+    /// Is below an arbitrary content root of the standard.
+    ArbitraryContent,
+    /// Is below a generated content root of the standard,
+    /// or fits a generated content regex otherwise.
+    GeneratedContent,
+}
+
+impl Status {
+    /// The name of this status, as used in serialized output.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::In => "in",
+            Self::Module => "module",
+            Self::Out => "out",
+            Self::Ignored => "ignored",
+            Self::ArbitraryContent => "arbitrary_content",
+            Self::GeneratedContent => "generated_content",
+        }
+    }
+}
+
+/// Indicates which part of a specific dir standard
+/// a single dir or file is covered by.
+#[derive(Debug, Serialize)]
+pub struct PathMapping {
+    /// The standard that the path was checked against
+    pub std: &'static DirStd,
+    /// The path as it was checked, relative to the project root
+    pub path: Rc<PathBuf>,
+    /// The chain of module dirs that contain this path,
+    /// outermost first.
+    /// Each one is relative to the previous one,
+    /// the first one to the project root.
+    pub modules: Vec<PathBuf>,
+    /// The category this path falls into
+    pub status: Status,
+    /// The records of the standard matching this path
+    pub records: Vec<&'static super::format::Rec<'static>>,
+    /// Whether this path is generated content,
+    /// which might be the case in addition to `status`
+    /// being [`Status::In`] or [`Status::ArbitraryContent`].
+    pub generated_content: bool,
+    /// The record expecting other file types than the one of this path,
+    /// see [`Coverage::misplaced`].
+    pub misplaced: Option<&'static Rec<'static>>,
+}
+
+impl PathMapping {
+    #[must_use]
+    pub const fn new(std: &'static DirStd, path: Rc<PathBuf>) -> Self {
+        Self {
+            std,
+            path,
+            modules: Vec::new(),
+            status: Status::Out,
+            records: Vec::new(),
+            generated_content: false,
+            misplaced: None,
+        }
+    }
+
+    /// The root dir of the innermost module containing this path,
+    /// relative to the project root.
+    #[must_use]
+    pub fn module_root(&self) -> Option<PathBuf> {
+        if self.modules.is_empty() {
+            None
+        } else {
+            Some(self.modules.iter().collect())
+        }
+    }
+
+    /// The tags of all the matched records, sorted and without duplicates.
+    #[must_use]
+    pub fn tags(&self) -> Vec<&'static str> {
+        let mut tags = self
+            .records
+            .iter()
+            .flat_map(|rec| rec.tags.iter().copied())
+            .filter(|tag| !tag.is_empty())
+            .collect::<Vec<_>>();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+}
+
+/// A flat version of a [`PathMapping`],
+/// suitable for CSV or NDJSON output,
+/// one row per input path.
+/// Multiple values within one column are separated by '|',
+/// like in the standards definition CSV files.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct MappingRow {
+    pub standard: String,
+    pub path: String,
+    pub status: Status,
+    pub records: String,
+    pub module: String,
+    pub tags: String,
 }
 
-fn create_arbitrary_content_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
+impl From<&PathMapping> for MappingRow {
+    fn from(mapping: &PathMapping) -> Self {
+        Self {
+            standard: mapping.std.name.to_owned(),
+            path: mapping.path.to_string_lossy().into_owned(),
+            status: mapping.status,
+            records: mapping
+                .records
+                .iter()
+                .map(|rec| rec.path)
+                .collect::<Vec<_>>()
+                .join("|"),
+            module: mapping
+                .module_root()
+                .map(|root| root.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            tags: mapping.tags().join("|"),
+        }
+    }
+}
+
+/// Aggregate counters of a [`Coverage`].
+///
+/// This does not keep any of the paths.
+/// As opposed to a [`Coverage`],
+/// its size does not grow with the size of the input listing,
+/// but is bounded by the number of records in the standard
+/// and the number of modules in the project.
+#[derive(Debug, Serialize)]
+pub struct CoverageStats {
+    /// The standard that coverage was checked for
+    pub std: &'static DirStd,
+    /// Number of viable paths in the input-dir,
+    /// see [`Coverage::num_paths`].
+    pub num_paths: usize,
+    /// The records in the checked standard
+    /// that matched one or more paths in the input,
+    /// together with the number of those matched paths.
+    pub r#in: HashMap<&'static super::format::Rec<'static>, usize>,
+    /// Number of ignored paths.
+    pub num_ignored: usize,
+    /// Number of paths below an arbitrary content root of the standard.
+    pub num_arbitrary_content: usize,
+    /// Number of paths that are generated content.
+    pub num_generated_content: usize,
+    /// Number of paths that are not of the expected file types,
+    /// see [`Coverage::misplaced`].
+    pub num_misplaced: usize,
+    /// Number of viable paths that did not match any record.
+    pub num_out: usize,
+    /// Number of viable paths that did not match any record,
+    /// and are files.
+    /// As listings usually do not tell files and dirs apart,
+    /// paths with an other path listed right before or after them
+    /// that is below them count as dirs,
+    /// as well as those listed with a trailing separator.
+    pub num_out_files: usize,
+    /// The stats for the modules directly included in the root listing,
+    /// see [`Coverage::modules`].
+    pub modules: HashMap<PathBuf, Self>,
+    /// The previously added path
+    #[serde(skip)]
+    last_path: Option<Rc<PathBuf>>,
+    /// The modules of the previously added path,
+    /// if it was counted as an out file
+    #[serde(skip)]
+    last_out_file_modules: Option<Vec<PathBuf>>,
+}
+
+impl CoverageStats {
+    #[must_use]
+    pub fn new(std: &'static DirStd) -> Self {
+        Self {
+            std,
+            num_paths: 0,
+            r#in: HashMap::new(),
+            num_ignored: 0,
+            num_arbitrary_content: 0,
+            num_generated_content: 0,
+            num_misplaced: 0,
+            num_out: 0,
+            num_out_files: 0,
+            modules: HashMap::new(),
+            last_path: None,
+            last_out_file_modules: None,
+        }
+    }
+
+    fn module_stats_mut(&mut self, modules: &[PathBuf]) -> &mut Self {
+        let mut stats = self;
+        for mod_dir in modules {
+            let std = stats.std;
+            stats = stats
+                .modules
+                .entry(mod_dir.clone())
+                .or_insert_with(|| Self::new(std));
+        }
+        stats
+    }
+
+    /// Adds a single classified path to the counters.
+    ///
+    /// To tell out files from out dirs without accessing the file-system,
+    /// each path is compared to the previous one,
+    /// which works for listings with the content of each dir
+    /// listed consecutively, see [`crate::listing::DirsAdder::new_streaming`].
+    pub fn add(&mut self, mapping: &PathMapping) {
+        let path = &mapping.path;
+        let last_path = self.last_path.replace(Rc::clone(path));
+        let is_below =
+            |path_a: &Path, path_b: &Path| path_a != path_b && path_a.starts_with(path_b);
+        if let Some(last_out_file_modules) = self.last_out_file_modules.take() {
+            if last_path.as_ref().is_some_and(|last| is_below(path, last)) {
+                // The previous path turns out to be a dir
+                let last_stats = self.module_stats_mut(&last_out_file_modules);
+                last_stats.num_out_files = last_stats.num_out_files.saturating_sub(1);
+            }
+        }
+        let is_dir = is_listed_as_dir(path) || last_path.is_some_and(|last| is_below(&last, path));
+        if mapping.status == Status::Out && !is_dir {
+            self.last_out_file_modules = Some(mapping.modules.clone());
+        }
+
+        let stats = self.module_stats_mut(&mapping.modules);
+        if mapping.status == Status::Ignored {
+            stats.num_ignored += 1;
+            return;
+        }
+        stats.num_paths += 1;
+        for rec in &mapping.records {
+            *stats.r#in.entry(rec).or_default() += 1;
+        }
+        if mapping.status == Status::ArbitraryContent {
+            stats.num_arbitrary_content += 1;
+        }
+        if mapping.generated_content {
+            stats.num_generated_content += 1;
+        }
+        if mapping.misplaced.is_some() {
+            stats.num_misplaced += 1;
+        }
+        if mapping.status == Status::Out {
+            stats.num_out += 1;
+            if !is_dir {
+                stats.num_out_files += 1;
+            }
+        }
+    }
+
+    /// Calculates how much the input listing adheres to the input dir standard,
+    /// the same way as [`Coverage::rate`].
+    #[must_use]
+    pub fn rate(&self) -> f32 {
+        combined_rating(
+            self.std,
+            self.r#in
+                .iter()
+                .filter(|(_record, num)| **num > 0)
+                .map(|(record, _num)| *record),
+            self.num_out_files,
+            self.num_paths,
+            self.modules
+                .values()
+                .map(|mod_stats| (mod_stats.num_paths, mod_stats.rate())),
+        )
+    }
+}
+
+/// Whether the path was listed with a trailing separator,
+/// which marks it as a dir, see [`crate::listing::Entry::from_listed`].
+fn is_listed_as_dir(path: &Path) -> bool {
+    path.as_os_str().to_string_lossy().ends_with(['/', '\\'])
+}
+
+/// Calculates how much the input listing adheres to the input dir standard.
+/// 0.0 means not at all, 1.0 means totally/fully.
+fn combined_rating<'a>(
+    std: &DirStd,
+    matched_recs: impl Iterator<Item = &'a super::format::Rec<'static>>,
+    num_out_files: usize,
+    num_paths: usize,
+    mod_parts: impl Iterator<Item = (usize, f32)>,
+) -> f32 {
+    let mut pos_rating = 0.0;
+    let mut matches_records = false;
+    for record in matched_recs {
+        pos_rating += record.indicativeness;
+        trace!("rp: {}", record.path);
+        trace!("rr: {:#?}", record.regex);
+        trace!("ri: {}", record.indicativeness);
+        matches_records = true;
+    }
+    if !matches_records {
+        return 0.0;
+    }
+
+    let mut ind_sum = 0.0;
+    for rec in &std.records {
+        ind_sum += rec.indicativeness;
+    }
+    let av_ind = ind_sum / std.records.len() as f32;
+
+    let neg_rating = num_out_files as f32 * av_ind;
+    trace!("ai: {av_ind}");
+    trace!("of: {num_out_files}");
+    trace!("nr: {neg_rating}");
+    trace!("pr: {pos_rating}");
+
+    let total_rating = pos_rating + neg_rating;
+    // the main rating is the whole rating, excluding the modules
+    let main_rating = if total_rating > 0.0 {
+        pos_rating / total_rating
+    } else {
+        pos_rating
+    };
+
+    let mut rating_parts = vec![(num_paths, main_rating)];
+    rating_parts.extend(mod_parts);
+    let num_combined_paths = rating_parts
+        .iter()
+        .fold(0, |sum, (num_paths, _part_rating)| sum + num_paths)
+        as f32;
+    let combined_rating = rating_parts
+        .iter()
+        .fold(0.0, |sum, (num_paths, part_rating)| {
+            sum + (part_rating * (*num_paths as f32 / num_combined_paths))
+        });
+    combined_rating
+}
+
+fn create_arbitrary_content_rgxs(std: &DirStd, tree_recs: &[RNode]) -> Result<Vec<Regex>, Error> {
     let mut rgxs = vec![];
     for rec_node in tree_recs {
         let rec_brw = rec_node.borrow();
@@ -97,9 +479,14 @@ fn create_arbitrary_content_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
                             let mut rgx_str = path_regex.0.to_string();
                             // This squeezes in before the final "$"
                             rgx_str.insert_str(rgx_str.len() - 1, "/.*");
-                            Regex::new(&rgx_str).unwrap_or_else(|_| {
-                                panic!("Bad (assembled) arbitrary content dir regex '{rgx_str}'")
-                            })
+                            Regex::new(&rgx_str).map_err(|_| {
+                                Error::invalid_standard(
+                                    std.name,
+                                    format!(
+                                        "Bad (assembled) arbitrary content dir regex '{rgx_str}'"
+                                    ),
+                                )
+                            })?
                         } else {
                             path_regex.0.clone()
                         };
@@ -109,10 +496,10 @@ fn create_arbitrary_content_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
             }
         }
     }
-    rgxs
+    Ok(rgxs)
 }
 
-fn create_generated_content_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
+fn create_generated_content_rgxs(std: &DirStd, tree_recs: &[RNode]) -> Result<Vec<Regex>, Error> {
     let mut rgxs = vec![];
     for rec_node in tree_recs {
         let rec_brw = rec_node.borrow();
@@ -123,9 +510,12 @@ fn create_generated_content_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
                         let mut rgx_str = path_regex.0.to_string();
                         // This squeezes in before the final "$"
                         rgx_str.insert_str(rgx_str.len() - 1, "/.*");
-                        Regex::new(&rgx_str).unwrap_or_else(|_| {
-                            panic!("Bad (assembled) generated content dir regex '{rgx_str}'")
-                        })
+                        Regex::new(&rgx_str).map_err(|_| {
+                            Error::invalid_standard(
+                                std.name,
+                                format!("Bad (assembled) generated content dir regex '{rgx_str}'"),
+                            )
+                        })?
                     } else {
                         path_regex.0.clone()
                     };
@@ -134,10 +524,10 @@ fn create_generated_content_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
             }
         }
     }
-    rgxs
+    Ok(rgxs)
 }
 
-fn create_module_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
+fn create_module_rgxs(std: &DirStd, tree_recs: &[RNode]) -> Result<Vec<Regex>, Error> {
     let mut rgxs = HashMap::new();
     log::warn!("module rgxs:");
     for rec_node in tree_recs {
@@ -151,9 +541,12 @@ fn create_module_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
                         rgx_str.remove(rgx_str.len() - 1);
                         rgx_str.insert(rgx_str.len(), '/');
                         log::warn!("{rgx_str}");
-                        Regex::new(&rgx_str).unwrap_or_else(|_| {
-                            panic!("Bad (assembled) module dir regex '{rgx_str}'")
-                        })
+                        Regex::new(&rgx_str).map_err(|_| {
+                            Error::invalid_standard(
+                                std.name,
+                                format!("Bad (assembled) module dir regex '{rgx_str}'"),
+                            )
+                        })?
                     } else {
                         path_regex.0.clone()
                     };
@@ -165,141 +558,255 @@ fn create_module_rgxs(tree_recs: &[RNode]) -> Vec<Regex> {
         }
     }
     log::warn!("");
-    rgxs.into_iter().map(|rgxeq| rgxeq.1).collect()
+    Ok(rgxs.into_iter().map(|rgxeq| rgxeq.1).collect())
+}
+
+fn create_file_type_rgxs(
+    std: &DirStd,
+    tree_recs: &[RNode<'static>],
+) -> Result<Vec<(Regex, &'static Rec<'static>)>, Error> {
+    let mut rgxs = vec![];
+    for rec_node in tree_recs {
+        let rec_brw = rec_node.borrow();
+        if let (Some(rec), Some(path_regex)) = (rec_brw.value, &rec_brw.path_regex) {
+            if rec.file_types.is_none() {
+                continue;
+            }
+            let rgx = if rec.directory {
+                let mut rgx_str = path_regex.0.to_string();
+                // This squeezes in before the final "$"
+                rgx_str.insert_str(rgx_str.len() - 1, "/.*");
+                Regex::new(&rgx_str).map_err(|_| {
+                    Error::invalid_standard(
+                        std.name,
+                        format!("Bad (assembled) file type content dir regex '{rgx_str}'"),
+                    )
+                })?
+            } else {
+                path_regex.0.clone()
+            };
+            rgxs.push((rgx, rec));
+        }
+    }
+    Ok(rgxs)
+}
+
+fn any_match(rgxs: &[Regex], dir_or_file: &str) -> bool {
+    rgxs.iter().any(|rgx| rgx.is_match(dir_or_file))
+}
+
+/// Returns the most specific record that expects certain file types
+/// of the given file, if the file is of none of them.
+fn misplaced_by(
+    file_type_rgxs: &[(Regex, &'static Rec<'static>)],
+    file_str: &str,
+    file: &Path,
+) -> Option<&'static Rec<'static>> {
+    file_type_rgxs
+        .iter()
+        .filter(|(rgx, _rec)| rgx.is_match(file_str))
+        .map(|(_rgx, rec)| *rec)
+        .max_by_key(|rec| rec.path.len())
+        .filter(|rec| !file_types::fits_record(rec, file))
 }
 
 impl Checker {
     /// Given a set of the relative paths of all dirs and files in a project,
     /// figures out which of them are covered by what parts
     /// of a given dir standard.
-    pub fn new(std: &'static super::format::DirStd, ignored_paths: &Regex) -> Self {
+    ///
+    /// # Errors
+    ///
+    /// If the regexes of the standard can not be assembled,
+    /// see [`tree::create`].
+    pub fn new(
+        std: &'static super::format::DirStd,
+        ignored_paths: &Regex,
+        policy: MatchPolicy,
+    ) -> Result<Self, Error> {
+        let matchers = Rc::new(Matchers::new(std, policy)?);
+        Ok(Self::with_matchers(std, ignored_paths, policy, matchers))
+    }
+
+    fn with_matchers(
+        std: &'static DirStd,
+        ignored_paths: &Regex,
+        policy: MatchPolicy,
+        matchers: Rc<Matchers>,
+    ) -> Self {
         Self {
             coverage: Coverage::new(std),
             ignored_paths: ignored_paths.clone(),
-            arbitrary_content_rgxs: None,
-            generated_content_rgxs: None,
-            module_rgxs: None,
+            policy,
+            matchers,
             modules: HashMap::new(),
-            records_tree: None,
         }
     }
 
     /// Creates a map of checkers with one entry for each standard.
-    pub fn new_all(ignored_paths: &Regex) -> Vec<Self> {
+    ///
+    /// # Errors
+    ///
+    /// If the regexes of any of the standards can not be assembled,
+    /// see [`tree::create`].
+    pub fn new_all(ignored_paths: &Regex, policy: MatchPolicy) -> Result<Vec<Self>, Error> {
         let mut checkers = Vec::new();
         for (_std_name, std_records) in super::data::STDS.iter() {
-            checkers.push(Self::new(std_records, ignored_paths));
+            checkers.push(Self::new(std_records, ignored_paths, policy)?);
         }
-        checkers
+        Ok(checkers)
     }
 
-    pub fn cover(&mut self, dir_or_file: &Rc<PathBuf>) {
-        let dir_or_file_str_lossy = dir_or_file.as_ref().to_string_lossy();
-
-        let (_recs_tree_root, tree_recs) = self
-            .records_tree
-            .get_or_insert_with(|| tree::create(self.coverage.std));
+    /// Figures out which part of the standard the given dir or file is covered by,
+    /// and records that in the coverage in creation.
+    pub fn cover(&mut self, dir_or_file: &Rc<PathBuf>) -> PathMapping {
+        self.map_path(dir_or_file, true)
+    }
 
-        // lazy-init module_rgxs
-        if self.module_rgxs.is_none() {
-            self.module_rgxs = Some(create_module_rgxs(tree_recs));
-        }
+    /// Figures out which part of the standard the given dir or file is covered by,
+    /// *without* recording it in the coverage in creation.
+    /// This allows to process huge listings in constant memory,
+    /// see [`CoverageStats`].
+    #[must_use]
+    pub fn classify(&mut self, dir_or_file: &Rc<PathBuf>) -> PathMapping {
+        self.map_path(dir_or_file, false)
+    }
 
-        for mod_rgx in self
-            .module_rgxs
-            .as_ref()
-            .expect("Was initialized further up in this function")
-        {
-            if let Some(mtch) =
-                mod_rgx
-                    .captures_iter(&dir_or_file_str_lossy)
-                    .next()
-                    .map(|capture| {
-                        capture
-                            .get(0)
-                            .expect("If a Module path matches, it should always have a first match")
-                    })
-            {
+    /// If the given dir or file is part of a module,
+    /// maps it with the checker of that module.
+    fn map_module_path(
+        &mut self,
+        dir_or_file: &Rc<PathBuf>,
+        dir_or_file_str_lossy: &str,
+        record: bool,
+    ) -> Option<PathMapping> {
+        let matchers = Rc::clone(&self.matchers);
+        for mod_rgx in &matchers.module_rgxs {
+            if let Some(mtch) = mod_rgx.find(dir_or_file_str_lossy) {
                 log::warn!("\nmodule related path: {dir_or_file_str_lossy}");
                 let mod_dir: PathBuf = mtch.as_str().into();
                 let sub_dir_or_file = Rc::new(PathBuf::from(
-                    mod_rgx.replace(&dir_or_file_str_lossy, "").as_ref(),
+                    mod_rgx.replace(dir_or_file_str_lossy, "").as_ref(),
                 ));
                 log::warn!("      mod_dir: {}", mod_dir.display());
                 log::warn!("      mod_dir stripped away: {sub_dir_or_file:?}");
-                self.modules
-                    .entry(mod_dir)
-                    .or_insert_with(|| Self::new(self.coverage.std, &self.ignored_paths))
-                    .cover(&sub_dir_or_file);
-                return;
+                let mut mapping = self
+                    .modules
+                    .entry(mod_dir.clone())
+                    .or_insert_with(|| {
+                        Self::with_matchers(
+                            self.coverage.std,
+                            &self.ignored_paths,
+                            self.policy,
+                            Rc::clone(&matchers),
+                        )
+                    })
+                    .map_path(&sub_dir_or_file, record);
+                mapping.path = Rc::clone(dir_or_file);
+                mapping.modules.insert(0, mod_dir);
+                return Some(mapping);
             }
         }
+        None
+    }
 
-        if self.ignored_paths.is_match(&dir_or_file_str_lossy) {
-            self.coverage.ignored.push(Rc::clone(dir_or_file));
-            return;
-        }
-        self.coverage.num_paths += 1;
+    fn map_path(&mut self, dir_or_file: &Rc<PathBuf>, record: bool) -> PathMapping {
+        let dir_or_file_str_raw = dir_or_file.as_ref().to_string_lossy();
+        let dir_or_file_str_lossy = self.policy.normalize_path(&dir_or_file_str_raw);
 
-        // lazy-init arbitrary_content_rgxs
-        if self.arbitrary_content_rgxs.is_none() {
-            self.arbitrary_content_rgxs = Some(create_arbitrary_content_rgxs(tree_recs));
+        if let Some(mapping) = self.map_module_path(dir_or_file, &dir_or_file_str_lossy, record) {
+            return mapping;
         }
 
-        // lazy-init generated_content_rgxs
-        if self.generated_content_rgxs.is_none() {
-            self.generated_content_rgxs = Some(create_generated_content_rgxs(tree_recs));
+        let matchers = Rc::clone(&self.matchers);
+        let (_recs_tree_root, tree_recs) = &matchers.records_tree;
+
+        let mut mapping = PathMapping::new(self.coverage.std, Rc::clone(dir_or_file));
+
+        if self.ignored_paths.is_match(&dir_or_file_str_lossy) {
+            mapping.status = Status::Ignored;
+            if record {
+                self.coverage.ignored.push(Rc::clone(dir_or_file));
+            }
+            return mapping;
+        }
+        if record {
+            self.coverage.num_paths += 1;
         }
 
         // NOTE This is the version using full(-relative)-path regexes
         //      -> much simpler and so far has more features
-        let mut matching = false;
         for rec_node in tree_recs {
             let rec_node_brwd = rec_node.borrow();
-            if let Some(path_regex) = &rec_node_brwd.path_regex {
+            if let (Some(path_regex), Some(rec)) = (&rec_node_brwd.path_regex, rec_node_brwd.value)
+            {
                 if path_regex.is_match(dir_or_file_str_lossy.as_ref()) {
-                    matching = true;
-                    let rec = rec_node_brwd
-                        .value
-                        .expect("A tree node with path_regex set should never have a None value");
-                    self.coverage
-                        .r#in
-                        .entry(rec)
-                        .or_default()
-                        .push(Rc::clone(dir_or_file));
+                    mapping.records.push(rec);
                 }
             }
         }
+        if record {
+            for rec in &mapping.records {
+                self.coverage
+                    .r#in
+                    .entry(rec)
+                    .or_default()
+                    .push(Rc::clone(dir_or_file));
+            }
+        }
+        let mut matching = !mapping.records.is_empty();
+        if matching {
+            mapping.status = if mapping.records.iter().any(|rec| rec.module) {
+                Status::Module
+            } else {
+                Status::In
+            };
+        }
 
-        if !matching {
-            let rgxs = self.arbitrary_content_rgxs.as_ref();
-            let cont = &mut self.coverage.arbitrary_content;
-            for rgx in rgxs.expect("Was initialized further up in this function") {
-                if rgx.is_match(&dir_or_file_str_lossy) {
-                    matching = true;
-                    cont.push(Rc::clone(dir_or_file));
-                    break;
-                }
+        if !matching && any_match(&matchers.arbitrary_content_rgxs, &dir_or_file_str_lossy) {
+            matching = true;
+            mapping.status = Status::ArbitraryContent;
+            if record {
+                self.coverage.arbitrary_content.push(Rc::clone(dir_or_file));
             }
         }
 
-        {
-            let rgxs = self.generated_content_rgxs.as_ref();
-            let cont = &mut self.coverage.generated_content;
-            for rgx in rgxs.expect("Was initialized further up in this function") {
-                if rgx.is_match(&dir_or_file_str_lossy) {
-                    matching = true;
-                    cont.push(Rc::clone(dir_or_file));
-                    break;
-                }
+        if any_match(&matchers.generated_content_rgxs, &dir_or_file_str_lossy) {
+            if !matching {
+                mapping.status = Status::GeneratedContent;
+            }
+            matching = true;
+            mapping.generated_content = true;
+            if record {
+                self.coverage.generated_content.push(Rc::clone(dir_or_file));
             }
         }
 
-        if !matching {
+        if !matching && record {
             self.coverage.out.push(Rc::clone(dir_or_file));
         }
+
+        if matches!(mapping.status, Status::In | Status::ArbitraryContent)
+            && !mapping.records.iter().any(|rec| rec.directory)
+        {
+            mapping.misplaced = misplaced_by(
+                &matchers.file_type_rgxs,
+                &dir_or_file_str_lossy,
+                dir_or_file,
+            );
+            if let (Some(rec), true) = (mapping.misplaced, record) {
+                self.coverage
+                    .misplaced
+                    .entry(rec)
+                    .or_default()
+                    .push(Rc::clone(dir_or_file));
+            }
+        }
+
+        mapping
     }
 
+    #[must_use]
     pub fn coverage(mut self) -> Coverage {
         self.coverage.modules.clear();
         for (mod_path, mod_checker) in self.modules {
@@ -322,71 +829,115 @@ impl Coverage {
             arbitrary_content: Vec::new(),
             generated_content: Vec::new(),
             out: Vec::new(),
+            misplaced: HashMap::new(),
             modules: HashMap::new(),
         }
     }
 
+    /// The number of out paths that are files.
+    ///
+    /// As listings usually do not tell files and dirs apart,
+    /// and the file-system is not accessed,
+    /// out paths with any other path of the listing below them count as dirs,
+    /// as well as those listed with a trailing separator.
+    #[must_use]
+    pub fn num_out_files(&self) -> usize {
+        let dirs = self
+            .r#in
+            .values()
+            .flatten()
+            .chain(&self.ignored)
+            .chain(&self.arbitrary_content)
+            .chain(&self.generated_content)
+            .chain(&self.out)
+            .map(|path| path.as_path())
+            .chain(self.modules.keys().map(PathBuf::as_path))
+            .flat_map(|path| path.ancestors().skip(1))
+            .collect::<HashSet<_>>();
+        self.out
+            .iter()
+            .filter(|path| !dirs.contains(path.as_path()) && !is_listed_as_dir(path))
+            .count()
+    }
+
     /// Calculates how much the input listing adheres to the input dir standard.
     /// 0.0 means not at all, 1.0 means totally/fully.
     #[must_use]
     pub fn rate(&self) -> f32 {
-        let mut pos_rating = 0.0;
-        let mut matches_records = false;
-        for (record, paths) in &self.r#in {
-            if !paths.is_empty() {
-                pos_rating += record.indicativeness;
-                trace!("rp: {}", record.path);
-                trace!("rr: {:#?}", record.regex);
-                trace!("ri: {}", record.indicativeness);
-                trace!("mps: {:#?}", paths);
-                matches_records = true;
+        let num_out_files = self.num_out_files();
+        trace!("out: {:#?}", self.out);
+        combined_rating(
+            self.std,
+            self.r#in
+                .iter()
+                .filter(|(_record, paths)| !paths.is_empty())
+                .map(|(record, _paths)| *record),
+            num_out_files,
+            self.num_paths,
+            self.modules
+                .values()
+                .map(|mod_coverage| (mod_coverage.num_paths, mod_coverage.rate())),
+        )
+    }
+
+    /// Reconstructs the mapping of each path in this coverage,
+    /// including the paths within modules, sorted by path.
+    #[must_use]
+    pub fn mappings(&self) -> Vec<PathMapping> {
+        fn mapping_of<'m>(
+            mappings: &'m mut HashMap<Rc<PathBuf>, PathMapping>,
+            std: &'static DirStd,
+            path: &Rc<PathBuf>,
+        ) -> &'m mut PathMapping {
+            mappings
+                .entry(Rc::clone(path))
+                .or_insert_with(|| PathMapping::new(std, Rc::clone(path)))
+        }
+
+        let mut mappings_map = HashMap::new();
+        for path in &self.out {
+            mapping_of(&mut mappings_map, self.std, path).status = Status::Out;
+        }
+        for path in &self.generated_content {
+            let mapping = mapping_of(&mut mappings_map, self.std, path);
+            mapping.status = Status::GeneratedContent;
+            mapping.generated_content = true;
+        }
+        for path in &self.arbitrary_content {
+            mapping_of(&mut mappings_map, self.std, path).status = Status::ArbitraryContent;
+        }
+        for (rec, paths) in &self.r#in {
+            for path in paths {
+                let mapping = mapping_of(&mut mappings_map, self.std, path);
+                mapping.records.push(rec);
+                mapping.status = if rec.module || mapping.status == Status::Module {
+                    Status::Module
+                } else {
+                    Status::In
+                };
             }
         }
-        if !matches_records {
-            return 0.0;
+        for (rec, paths) in &self.misplaced {
+            for path in paths {
+                mapping_of(&mut mappings_map, self.std, path).misplaced = Some(rec);
+            }
         }
-
-        let mut ind_sum = 0.0;
-        for rec in &self.std.records {
-            ind_sum += rec.indicativeness;
+        for path in &self.ignored {
+            mapping_of(&mut mappings_map, self.std, path).status = Status::Ignored;
         }
-        let av_ind = ind_sum / self.std.records.len() as f32;
-
-        let num_out_files = self
-            .out
-            .iter()
-            .filter(|path_buf| path_buf.as_path().is_file())
-            .count();
-        let neg_rating = num_out_files as f32 * av_ind;
-        // trace!("{:#?}", self);
-        trace!("ai: {av_ind}");
-        trace!("of: {num_out_files}");
-        trace!("nr: {neg_rating}");
-        trace!("pr: {pos_rating}");
-        trace!("out: {:#?}", self.out);
-
-        let total_rating = pos_rating + neg_rating;
-        // the main rating is the whole rating, excluding the modules
-        let main_rating = if total_rating > 0.0 {
-            pos_rating / total_rating
-        } else {
-            pos_rating
-        };
-
-        let mut rating_parts = vec![(self.num_paths, main_rating)];
-        for mod_coverage in self.modules.values() {
-            rating_parts.push((mod_coverage.num_paths, mod_coverage.rate()));
+        let mut mappings = mappings_map.into_values().collect::<Vec<_>>();
+        for (mod_dir, mod_coverage) in &self.modules {
+            for mut mapping in mod_coverage.mappings() {
+                mapping.path = Rc::new(mod_dir.join(mapping.path.as_path()));
+                mapping.modules.insert(0, mod_dir.clone());
+                mappings.push(mapping);
+            }
         }
-        let num_combined_paths = rating_parts
-            .iter()
-            .fold(0, |sum, (num_paths, _part_rating)| sum + num_paths)
-            as f32;
-        let combined_rating = rating_parts
-            .iter()
-            .fold(0.0, |sum, (num_paths, part_rating)| {
-                sum + (part_rating * (*num_paths as f32 / num_combined_paths))
-            });
-        combined_rating
+        for mapping in &mut mappings {
+            mapping.records.sort_by_key(|rec| rec.path);
+        }
+        mappings.sort_by(|mapping_a, mapping_b| mapping_a.path.cmp(&mapping_b.path));
+        mappings
     }
 
     /// Returns a list of the identified module(/parts) directories.
@@ -414,12 +965,18 @@ impl Coverage {
 /// # Errors
 ///
 /// If any of the input listing entries is an error,
-/// usually caused by an I/O issue.
-pub fn cover_listing<T, E>(dirs_and_files: T, ignored_paths: &Regex) -> Result<Vec<Coverage>, E>
+/// usually caused by an I/O issue,
+/// or if the regexes of a standard can not be assembled.
+pub fn cover_listing<T, E>(
+    dirs_and_files: T,
+    ignored_paths: &Regex,
+    policy: MatchPolicy,
+) -> Result<Vec<Coverage>, Error>
 where
     T: Iterator<Item = Result<Rc<PathBuf>, E>>,
+    Error: From<E>,
 {
-    let mut checkers = Checker::new_all(ignored_paths);
+    let mut checkers = Checker::new_all(ignored_paths, policy)?;
     for dir_or_file_res in dirs_and_files {
         let dir_or_file = dir_or_file_res?;
         for checker in &mut checkers {
@@ -440,16 +997,19 @@ where
 /// # Errors
 ///
 /// If any of the input listing entries is an error,
-/// usually caused by an I/O issue.
+/// usually caused by an I/O issue,
+/// or if the regexes of the standard can not be assembled.
 pub fn cover_listing_with<T, E>(
     dirs_and_files: T,
     ignored_paths: &Regex,
+    policy: MatchPolicy,
     std: &'static DirStd,
-) -> Result<Coverage, E>
+) -> Result<Coverage, Error>
 where
     T: Iterator<Item = Result<Rc<PathBuf>, E>>,
+    Error: From<E>,
 {
-    let mut checker = Checker::new(std, ignored_paths);
+    let mut checker = Checker::new(std, ignored_paths, policy)?;
     for dir_or_file_res in dirs_and_files {
         let dir_or_file = dir_or_file_res?;
         checker.cover(&dir_or_file);
@@ -457,16 +1017,6 @@ where
     Ok(checker.coverage())
 }
 
-#[derive(Error, Debug)]
-pub enum Error {
-    #[error("Failed to evaluate the best fit, because: {0:?}")]
-    BestFitError(#[from] BestFitError),
-
-    /// Represents all other cases of `std::io::Error`.
-    #[error(transparent)]
-    IO(#[from] std::io::Error),
-}
-
 /// Given a set of the relative paths of all dirs and files in a project,
 /// for each of the known dir standards from
 /// <https://github.com/hoijui/osh-dir-std/>,
@@ -477,15 +1027,13 @@ pub enum Error {
 /// # Errors
 ///
 /// If any of the input listing entries is an error,
-/// usually caused by an I/O issue.
-///
-/// # Panics
-///
-/// Expecting `Option`s that logically have to be `Some`,
-/// thus this should never panic in practice.
+/// usually caused by an I/O issue,
+/// if a standard is unknown or invalid,
+/// or if no standard fits at all in case of [`Standards::BestFit`].
 pub fn cover_listing_by_stds<T>(
     dirs_and_files: T,
     ignored_paths: &Regex,
+    policy: MatchPolicy,
     stds: &Standards,
 ) -> Result<Vec<Coverage>, Error>
 where
@@ -493,29 +1041,115 @@ where
 {
     Ok(match stds {
         Standards::Default => {
-            let std = STDS.get(DEFAULT_STD_NAME).expect(
-                "This name was chosen from the data its self, so it should always be available",
-            );
-            vec![cover_listing_with(dirs_and_files, ignored_paths, std)?]
+            let std = stds::by_name(DEFAULT_STD_NAME)?;
+            vec![cover_listing_with(
+                dirs_and_files,
+                ignored_paths,
+                policy,
+                std,
+            )?]
         }
-        Standards::All => cover_listing(dirs_and_files, ignored_paths)?,
-        Standards::BestFit => {
-            let coverages = cover_listing(dirs_and_files, ignored_paths)?;
+        Standards::All => cover_listing(dirs_and_files, ignored_paths, policy)?,
+        Standards::BestFit(thresholds) => {
+            let coverages = cover_listing(dirs_and_files, ignored_paths, policy)?;
             let ratings = coverages
                 .into_iter()
                 .map(|coverage| RatingCont {
-                    rating: Rating::rate_coverage(&coverage),
+                    rating: Rating {
+                        name: coverage.std.name.to_owned(),
+                        factor: coverage.rate(),
+                    },
                     coverage: Some(coverage),
                 })
                 .collect();
-            let max_rating = best_fit(ratings)?;
-            vec![max_rating
-                .coverage
-                .expect("At this point, all coverages have to be present")]
+            let chosen = Ranking::new(ratings, thresholds).into_chosen()?;
+            chosen.coverage.into_iter().collect()
         }
         Standards::Specific(std_name) => {
-            let std = STDS.get(std_name).expect("Clap already checked the name!");
-            vec![cover_listing_with(dirs_and_files, ignored_paths, std)?]
+            let std = stds::by_name(std_name)?;
+            vec![cover_listing_with(
+                dirs_and_files,
+                ignored_paths,
+                policy,
+                std,
+            )?]
         }
     })
 }
+
+/// Like [`cover_listing_by_stds`], but without keeping the paths in memory.
+///
+/// Given a set of the relative paths of all dirs and files in a project,
+/// for the given directory standards,
+/// calculate what record of the standard each dir or file might be covered under.
+/// Each path is reported to `on_mapping` right after it was classified,
+/// once for each of the standards.
+/// Only aggregate counters are kept,
+/// which allows to process huge listings in (about) constant memory.
+///
+/// In case of [`Standards::BestFit`],
+/// the mappings for all standards are reported,
+/// because the best fit is only known in the end,
+/// and only the stats of the best fit are returned.
+///
+/// # Errors
+///
+/// If any of the input listing entries is an error,
+/// usually caused by an I/O issue,
+/// if `on_mapping` returns an error,
+/// if a standard is unknown or invalid,
+/// or if no standard fits at all in case of [`Standards::BestFit`].
+pub fn stream_listing_by_stds<T, F>(
+    dirs_and_files: T,
+    ignored_paths: &Regex,
+    policy: MatchPolicy,
+    stds: &Standards,
+    mut on_mapping: F,
+) -> Result<Vec<CoverageStats>, Error>
+where
+    T: Iterator<Item = Result<Rc<PathBuf>, io::Error>>,
+    F: FnMut(&PathMapping) -> Result<(), io::Error>,
+{
+    let mut checkers = match stds {
+        Standards::Default => vec![Checker::new(
+            stds::by_name(DEFAULT_STD_NAME)?,
+            ignored_paths,
+            policy,
+        )?],
+        Standards::All | Standards::BestFit(_) => Checker::new_all(ignored_paths, policy)?,
+        Standards::Specific(std_name) => {
+            vec![Checker::new(
+                stds::by_name(std_name)?,
+                ignored_paths,
+                policy,
+            )?]
+        }
+    };
+    let mut stats = checkers
+        .iter()
+        .map(|checker| CoverageStats::new(checker.coverage.std))
+        .collect::<Vec<_>>();
+    for dir_or_file_res in dirs_and_files {
+        let dir_or_file = dir_or_file_res?;
+        for (checker, std_stats) in checkers.iter_mut().zip(stats.iter_mut()) {
+            let mapping = checker.classify(&dir_or_file);
+            std_stats.add(&mapping);
+            on_mapping(&mapping)?;
+        }
+    }
+    if let Standards::BestFit(thresholds) = stds {
+        let ratings = stats
+            .iter()
+            .map(|std_stats| RatingCont {
+                rating: Rating {
+                    name: std_stats.std.name.to_owned(),
+                    factor: std_stats.rate(),
+                },
+                coverage: None,
+            })
+            .collect();
+        let chosen = Ranking::new(ratings, thresholds).into_chosen()?;
+        stats.retain(|std_stats| std_stats.std.name == chosen.rating.name);
+    }
+    Ok(stats)
+}
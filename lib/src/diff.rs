@@ -0,0 +1,193 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    path::PathBuf,
+    rc::Rc,
+};
+
+use crate::{
+    format::{DirStd, Rec},
+    Coverage, Error,
+};
+
+/// Indicates how the coverage of a project by a specific dir standard
+/// changed between two listings (e.g. two revisions) of that project.
+#[derive(Debug, Serialize)]
+pub struct CoverageDiff {
+    /// The standard that both coverages were checked for
+    pub std: &'static DirStd,
+    /// The rating of the old coverage.
+    pub rating_old: f32,
+    /// The rating of the new coverage.
+    pub rating_new: f32,
+    /// `rating_new - rating_old`;
+    /// positive means the project conforms better now.
+    pub rating_delta: f32,
+    /// The records that matched no path in the old listing,
+    /// but at least one in the new one.
+    pub records_matched: Vec<&'static Rec<'static>>,
+    /// The records that matched at least one path in the old listing,
+    /// but none in the new one.
+    pub records_unmatched: Vec<&'static Rec<'static>>,
+    /// The viable paths that did not match any record
+    /// in the new listing, but were not in that category before.
+    pub out_added: Vec<Rc<PathBuf>>,
+    /// The viable paths that did not match any record
+    /// in the old listing, but are not in that category anymore.
+    pub out_removed: Vec<Rc<PathBuf>>,
+    /// Module directories only present in the new listing.
+    pub modules_added: Vec<PathBuf>,
+    /// Module directories only present in the old listing.
+    pub modules_removed: Vec<PathBuf>,
+    /// The diffs of the modules present in both listings,
+    /// excluding those without any change.
+    pub modules_changed: BTreeMap<PathBuf, Self>,
+}
+
+fn matched_records(coverage: &Coverage) -> Vec<&'static Rec<'static>> {
+    coverage
+        .r#in
+        .iter()
+        .filter(|(_rec, paths)| !paths.is_empty())
+        .map(|(rec, _paths)| *rec)
+        .collect()
+}
+
+fn records_diff(coverage: &Coverage, other: &Coverage) -> Vec<&'static Rec<'static>> {
+    let other_rec_paths = matched_records(other)
+        .into_iter()
+        .map(|rec| rec.path)
+        .collect::<HashSet<_>>();
+    let mut recs = matched_records(coverage)
+        .into_iter()
+        .filter(|rec| !other_rec_paths.contains(rec.path))
+        .collect::<Vec<_>>();
+    recs.sort_by_key(|rec| rec.path);
+    recs
+}
+
+fn paths_diff(paths: &[Rc<PathBuf>], other: &[Rc<PathBuf>]) -> Vec<Rc<PathBuf>> {
+    let other_paths = other.iter().collect::<HashSet<_>>();
+    let mut diff = paths
+        .iter()
+        .filter(|path| !other_paths.contains(path))
+        .map(Rc::clone)
+        .collect::<Vec<_>>();
+    diff.sort();
+    diff
+}
+
+impl CoverageDiff {
+    /// Whether there is any difference at all between the two coverages.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rating_delta == 0.0
+            && self.records_matched.is_empty()
+            && self.records_unmatched.is_empty()
+            && self.out_added.is_empty()
+            && self.out_removed.is_empty()
+            && self.modules_added.is_empty()
+            && self.modules_removed.is_empty()
+            && self.modules_changed.is_empty()
+    }
+
+    fn fmt_indented(&self, f: &mut std::fmt::Formatter<'_>, indent: &str) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{indent}rating: {:.4} -> {:.4} ({:+.4})",
+            self.rating_old, self.rating_new, self.rating_delta
+        )?;
+        for rec in &self.records_matched {
+            writeln!(f, "{indent}+ record: {}", rec.path)?;
+        }
+        for rec in &self.records_unmatched {
+            writeln!(f, "{indent}- record: {}", rec.path)?;
+        }
+        for path in &self.out_added {
+            writeln!(f, "{indent}+ out: {}", path.display())?;
+        }
+        for path in &self.out_removed {
+            writeln!(f, "{indent}- out: {}", path.display())?;
+        }
+        for path in &self.modules_added {
+            writeln!(f, "{indent}+ module: {}", path.display())?;
+        }
+        for path in &self.modules_removed {
+            writeln!(f, "{indent}- module: {}", path.display())?;
+        }
+        let sub_indent = format!("{indent}    ");
+        for (path, mod_diff) in &self.modules_changed {
+            writeln!(f, "{indent}~ module: {}", path.display())?;
+            mod_diff.fmt_indented(f, &sub_indent)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CoverageDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "standard: {}", self.std.name)?;
+        self.fmt_indented(f, "  ")
+    }
+}
+
+/// Calculates the difference between two coverages of the same standard,
+/// for example of the listings of two revisions of a project.
+///
+/// # Errors
+///
+/// If the two coverages were not created for the same standard.
+pub fn diff(old: &Coverage, new: &Coverage) -> Result<CoverageDiff, Error> {
+    if old.std.name != new.std.name {
+        return Err(Error::StandardMismatch {
+            old: old.std.name,
+            new: new.std.name,
+        });
+    }
+
+    let rating_old = old.rate();
+    let rating_new = new.rate();
+
+    let mut modules_added = new
+        .modules
+        .keys()
+        .filter(|mod_path| !old.modules.contains_key(*mod_path))
+        .cloned()
+        .collect::<Vec<_>>();
+    modules_added.sort();
+    let mut modules_removed = old
+        .modules
+        .keys()
+        .filter(|mod_path| !new.modules.contains_key(*mod_path))
+        .cloned()
+        .collect::<Vec<_>>();
+    modules_removed.sort();
+    let mut modules_changed = BTreeMap::new();
+    for (mod_path, old_mod_cov) in &old.modules {
+        if let Some(new_mod_cov) = new.modules.get(mod_path) {
+            let mod_diff = diff(old_mod_cov, new_mod_cov)?;
+            if !mod_diff.is_empty() {
+                modules_changed.insert(mod_path.clone(), mod_diff);
+            }
+        }
+    }
+
+    Ok(CoverageDiff {
+        std: new.std,
+        rating_old,
+        rating_new,
+        rating_delta: rating_new - rating_old,
+        records_matched: records_diff(new, old),
+        records_unmatched: records_diff(old, new),
+        out_added: paths_diff(&new.out, &old.out),
+        out_removed: paths_diff(&old.out, &new.out),
+        modules_added,
+        modules_removed,
+        modules_changed,
+    })
+}
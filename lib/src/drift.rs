@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{fmt::Display, fs, path::Path, path::PathBuf, rc::Rc};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::{evaluation::rate_listing, matching::MatchPolicy, Error, Rating, RatingCont};
+
+/// The name of the TOML table that holds the declared standard,
+/// for example in a projects `okh.toml`:
+///
+/// ```toml
+/// [osh-dir-std]
+/// standard = "unixish"
+/// ```
+pub const DECLARATION_TABLE: &str = "osh-dir-std";
+/// The key within [`DECLARATION_TABLE`] that holds the name of the declared standard.
+pub const DECLARATION_KEY: &str = "standard";
+
+/// How well the standard a project declares to follow
+/// still fits it, compared to the best fitting one.
+#[derive(Debug, Clone, Serialize)]
+pub struct Drift {
+    /// The rating of the declared standard.
+    pub declared: Rating,
+    /// The rating of the best fitting standard
+    /// (which might be the declared one).
+    pub best: Rating,
+    /// By how much a different standard has to fit better
+    /// than the declared one, to count as drift.
+    pub min_margin: f32,
+    /// Whether a different standard fits better than the declared one
+    /// by at least `min_margin`.
+    pub drifted: bool,
+}
+
+impl Drift {
+    /// Compares the rating of the declared standard
+    /// with the best of the given ones.
+    ///
+    /// # Errors
+    ///
+    /// If the declared standard is not among the ratings.
+    pub fn new(declared: &str, ratings: &[RatingCont], min_margin: f32) -> Result<Self, Error> {
+        let declared_rating = ratings
+            .iter()
+            .map(|rating_cont| &rating_cont.rating)
+            .find(|rating| rating.name == declared)
+            .ok_or_else(|| Error::unknown_standard(declared))?;
+        let best = ratings
+            .iter()
+            .map(|rating_cont| &rating_cont.rating)
+            // NOTE On equal factors, the declared standard wins.
+            .fold(declared_rating, |best, rating| {
+                if rating.factor > best.factor {
+                    rating
+                } else {
+                    best
+                }
+            });
+        Ok(Self {
+            drifted: best.factor - declared_rating.factor >= min_margin
+                && best.name != declared_rating.name,
+            declared: declared_rating.clone(),
+            best: best.clone(),
+            min_margin,
+        })
+    }
+}
+
+impl Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "declared: {} ({})",
+            self.declared.name, self.declared.factor
+        )?;
+        writeln!(f, "best fit: {} ({})", self.best.name, self.best.factor)?;
+        if self.drifted {
+            writeln!(
+                f,
+                "drifted: '{}' fits better than the declared '{}' by at least {}",
+                self.best.name, self.declared.name, self.min_margin
+            )
+        } else {
+            writeln!(f, "no drift")
+        }
+    }
+}
+
+/// Extracts the name of the declared standard from TOML content,
+/// see [`DECLARATION_TABLE`].
+///
+/// # Errors
+///
+/// If the content is not valid TOML,
+/// or it contains no (valid) declaration.
+pub fn parse_declared_std(toml_content: &str, source: &Path) -> Result<String, Error> {
+    let invalid = |reason: String| Error::InvalidDeclaration {
+        file: source.to_path_buf(),
+        reason,
+    };
+    let table = toml_content
+        .parse::<toml::Table>()
+        .map_err(|err| invalid(err.to_string()))?;
+    table
+        .get(DECLARATION_TABLE)
+        .and_then(|decl| decl.get(DECLARATION_KEY))
+        .and_then(toml::Value::as_str)
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| {
+            invalid(format!(
+                "No string value '{DECLARATION_KEY}' in table '[{DECLARATION_TABLE}]'"
+            ))
+        })
+}
+
+/// Reads the name of the declared standard from a TOML file,
+/// for example the projects `okh.toml`.
+///
+/// # Errors
+///
+/// If the file could not be read,
+/// or it contains no (valid) declaration.
+pub fn read_declared_std(file: &Path) -> Result<String, Error> {
+    let content = fs::read_to_string(file).map_err(|err| Error::InvalidDeclaration {
+        file: file.to_path_buf(),
+        reason: err.to_string(),
+    })?;
+    parse_declared_std(&content, file)
+}
+
+/// Rates the listing with all the standards,
+/// and checks whether a different one than the declared one
+/// fits better by at least `min_margin`.
+///
+/// # Errors
+///
+/// If the declared standard is unknown,
+/// or reading the listing failed.
+pub fn check_drift<T, E>(
+    dirs_and_files: T,
+    ignored_paths: &Regex,
+    policy: MatchPolicy,
+    declared: &str,
+    min_margin: f32,
+) -> Result<Drift, Error>
+where
+    T: Iterator<Item = Result<Rc<PathBuf>, E>>,
+    Error: From<E>,
+{
+    crate::stds::by_name(declared)?;
+    let ratings = rate_listing(dirs_and_files, ignored_paths, policy)?;
+    Drift::new(declared, &ratings, min_margin)
+}
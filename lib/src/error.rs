@@ -0,0 +1,79 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::{data::STD_NAMES, evaluation::BestFitError};
+
+/// How similar (by Jaro-Winkler) a known standards name has to be
+/// to the requested one, to be suggested as an alternative.
+pub const SUGGESTION_MIN_SIMILARITY: f64 = 0.7;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Unknown directory standard: '{name}'{}", fmt_suggestions(.suggestions))]
+    UnknownStandard {
+        name: String,
+        /// Names of known standards similar to `name`,
+        /// the most similar one first.
+        suggestions: Vec<String>,
+    },
+
+    #[error("Invalid directory standard '{std}': {reason}")]
+    InvalidStandard { std: String, reason: String },
+
+    #[error("Failed to read the declared standard from '{}': {reason}", .file.display())]
+    InvalidDeclaration { file: PathBuf, reason: String },
+
+    #[error("Can not diff coverages of different standards: '{old}' vs '{new}'")]
+    StandardMismatch {
+        old: &'static str,
+        new: &'static str,
+    },
+
+    #[error("Failed to evaluate the best fit, because: {0:?}")]
+    BestFitError(#[from] BestFitError),
+
+    /// Represents all other cases of `std::io::Error`.
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+}
+
+fn fmt_suggestions(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!("; did you mean: '{}'?", suggestions.join("', '"))
+    }
+}
+
+impl Error {
+    /// Creates an [`Error::UnknownStandard`],
+    /// suggesting the known standards with similar names.
+    #[must_use]
+    pub fn unknown_standard(name: &str) -> Self {
+        let mut scored = STD_NAMES
+            .iter()
+            .map(|known| (strsim::jaro_winkler(name, known), *known))
+            .filter(|(score, _known)| *score >= SUGGESTION_MIN_SIMILARITY)
+            .collect::<Vec<_>>();
+        scored.sort_by(|(score_a, _), (score_b, _)| score_b.total_cmp(score_a));
+        Self::UnknownStandard {
+            name: name.to_owned(),
+            suggestions: scored
+                .into_iter()
+                .map(|(_score, known)| known.to_owned())
+                .collect(),
+        }
+    }
+
+    pub(crate) fn invalid_standard(std: &str, reason: impl Into<String>) -> Self {
+        Self::InvalidStandard {
+            std: std.to_owned(),
+            reason: reason.into(),
+        }
+    }
+}
@@ -7,18 +7,22 @@ use std::{path::PathBuf, rc::Rc};
 
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
-use tracing::trace;
 
-use crate::{cover_listing, coverage::cover_listing_with, data::STDS, stds::Standards, Coverage};
+use crate::{
+    cover_listing,
+    coverage::cover_listing_with,
+    matching::MatchPolicy,
+    stds::{self, Standards},
+    Coverage, Error,
+};
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Rating {
     pub name: String,
     pub factor: f32,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 pub struct RatingCont {
     pub rating: Rating,
     pub coverage: Option<Coverage>,
@@ -37,40 +41,14 @@ impl RatingCont {
 impl Rating {
     /// Calculates how much the input listing adheres to the input dir standard.
     /// 0.0 means not at all, 1.0 means totally/fully.
+    ///
+    /// See [`Coverage::rate`].
     #[must_use]
     pub fn rate_coverage(coverage: &Coverage) -> Self {
-        let mut pos_rating = 0.0;
-        let mut matches_records = false;
-        for (record, paths) in &coverage.r#in {
-            if !paths.is_empty() {
-                pos_rating += record.indicativeness;
-                trace!("rp: {}", record.path);
-                trace!("rr: {:#?}", record.regex);
-                trace!("ri: {}", record.indicativeness);
-                trace!("mps: {:#?}", paths);
-                matches_records = true;
-            }
-        }
-        let name = coverage.std.name.to_owned();
-        if !matches_records {
-            return Self { name, factor: 0.0 };
-        }
-
-        let mut ind_sum = 0.0;
-        for rec in &coverage.std.records {
-            ind_sum += rec.indicativeness;
+        Self {
+            name: coverage.std.name.to_owned(),
+            factor: coverage.rate(),
         }
-        let av_ind = ind_sum / coverage.std.records.len() as f32;
-
-        let neg_rating = coverage.out.len() as f32 * av_ind;
-        // trace!("{:#?}", self);
-        trace!("ai: {}", av_ind);
-        trace!("nr: {}", neg_rating);
-        trace!("pr: {}", pos_rating);
-        trace!("out: {:#?}", coverage.out);
-
-        let factor = pos_rating / (pos_rating + neg_rating);
-        Self { name, factor }
     }
 }
 
@@ -83,12 +61,18 @@ impl Rating {
 /// # Errors
 ///
 /// If any of the input listing entries is an error,
-/// usually caused by an I/O issue.
-pub fn rate_listing<T, E>(dirs_and_files: T, ignored_paths: &Regex) -> Result<Vec<RatingCont>, E>
+/// usually caused by an I/O issue,
+/// or if the regexes of a standard can not be assembled.
+pub fn rate_listing<T, E>(
+    dirs_and_files: T,
+    ignored_paths: &Regex,
+    policy: MatchPolicy,
+) -> Result<Vec<RatingCont>, Error>
 where
     T: Iterator<Item = Result<Rc<PathBuf>, E>>,
+    Error: From<E>,
 {
-    let coverages = cover_listing(dirs_and_files, ignored_paths)?;
+    let coverages = cover_listing(dirs_and_files, ignored_paths, policy)?;
     let mut ratings = vec![];
     for coverage in coverages {
         ratings.push(RatingCont {
@@ -110,23 +94,21 @@ where
 /// # Errors
 ///
 /// If any of the input listing entires is an error,
-/// usually caused by an I/O issue.
-///
-/// # Panics
-///
-/// If `std_name` does not equal any known directory standards name.
+/// usually caused by an I/O issue,
+/// if `std_name` does not equal any known directory standards name,
+/// or if the regexes of the standard can not be assembled.
 pub fn rate_listing_with<T, E>(
     dirs_and_files: T,
     ignored_paths: &Regex,
+    policy: MatchPolicy,
     std_name: &str,
-) -> Result<RatingCont, E>
+) -> Result<RatingCont, Error>
 where
     T: Iterator<Item = Result<Rc<PathBuf>, E>>,
+    Error: From<E>,
 {
-    let std = STDS
-        .get(std_name)
-        .unwrap_or_else(|| panic!("Unknown directory standard: '{std_name}'"));
-    let coverage = cover_listing_with(dirs_and_files, ignored_paths, std)?;
+    let std = stds::by_name(std_name)?;
+    let coverage = cover_listing_with(dirs_and_files, ignored_paths, policy, std)?;
     Ok(RatingCont {
         rating: Rating {
             name: std_name.to_string(),
@@ -136,18 +118,146 @@ where
     })
 }
 
-#[derive(Error, Debug)]
+#[derive(thiserror::Error, Debug)]
 pub enum BestFitError {
-    #[error("None of the supplied ratings has a factor higher then 0.0")]
+    #[error("None of the supplied ratings reaches the minimum factor")]
+    NoneViable,
+    #[error("The chosen standard '{0}' was not rated")]
+    ChosenNotRated(String),
+}
+
+/// When the best fitting standard is considered convincing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BestFitThresholds {
+    /// The minimum factor the best fitting standard needs to have,
+    /// to be considered viable at all.
+    pub min_factor: f32,
+    /// The minimum difference between the factors
+    /// of the best fitting and the runner-up standard,
+    /// for the best fit to be considered unambiguous.
+    pub min_margin: f32,
+    /// Whether to choose the default standard
+    /// if the best fit is not convincing.
+    pub fallback_to_default: bool,
+}
+
+impl Default for BestFitThresholds {
+    fn default() -> Self {
+        Self {
+            min_factor: 0.2,
+            min_margin: 0.05,
+            fallback_to_default: true,
+        }
+    }
+}
+
+/// How convincing the best fitting standard is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// The best fit is viable, and clearly ahead of the runner-up.
+    Clear,
+    /// The best fit is viable, but the runner-up is (about) as good.
+    Ambiguous,
+    /// Not even the best fit reaches the minimum factor.
     NoneViable,
 }
 
-/// Given a set of ratings, filters out the one with the higest factor.
+/// The ratings of multiple standards, best fitting first,
+/// together with how convincing the best fit is.
+#[derive(Debug, Serialize)]
+pub struct Ranking {
+    /// All the ratings, ordered by factor, highest first;
+    /// of equal factors, the first supplied comes first.
+    pub ratings: Vec<RatingCont>,
+    /// The factor of the best fit minus the one of the runner-up,
+    /// if there are at least two ratings.
+    pub margin: Option<f32>,
+    pub verdict: Verdict,
+    /// The name of the standard to use;
+    /// the best fit if the verdict is [`Verdict::Clear`],
+    /// or else the default one if falling back is enabled.
+    /// `None` if there is no viable standard and no fallback.
+    pub chosen: Option<String>,
+    /// Whether `chosen` is the result of falling back to the default standard.
+    pub fallback: bool,
+}
+
+impl Ranking {
+    /// Ranks the given ratings according to the given thresholds.
+    #[must_use]
+    pub fn new(mut ratings: Vec<RatingCont>, thresholds: &BestFitThresholds) -> Self {
+        // NOTE This is a stable sort, so equal factors keep their order.
+        ratings.sort_by(|rc_a, rc_b| rc_b.rating.factor.total_cmp(&rc_a.rating.factor));
+        let best = ratings.first().map(|rating_cont| &rating_cont.rating);
+        let margin = ratings
+            .get(1)
+            .zip(best)
+            .map(|(runner_up, first)| first.factor - runner_up.rating.factor);
+        let verdict = match best {
+            Some(first) if first.factor >= thresholds.min_factor => {
+                if margin.is_some_and(|diff| diff < thresholds.min_margin) {
+                    Verdict::Ambiguous
+                } else {
+                    Verdict::Clear
+                }
+            }
+            _ => Verdict::NoneViable,
+        };
+        let fallback = verdict != Verdict::Clear && thresholds.fallback_to_default;
+        let chosen = if fallback {
+            Some(crate::DEFAULT_STD_NAME.to_owned())
+        } else if verdict == Verdict::NoneViable {
+            None
+        } else {
+            best.map(|first| first.name.clone())
+        };
+        Self {
+            ratings,
+            margin,
+            verdict,
+            chosen,
+            fallback,
+        }
+    }
+
+    /// Removes the coverages from all the ratings.
+    #[must_use]
+    pub fn remove_coverages(self) -> Self {
+        Self {
+            ratings: self
+                .ratings
+                .into_iter()
+                .map(RatingCont::remove_coverage)
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Returns the rating of the chosen standard.
+    ///
+    /// # Errors
+    ///
+    /// If no standard was chosen ([`BestFitError::NoneViable`]),
+    /// or the chosen (default) one was not part of the ranking
+    /// ([`BestFitError::ChosenNotRated`]).
+    pub fn into_chosen(self) -> Result<RatingCont, BestFitError> {
+        let chosen = self.chosen.ok_or(BestFitError::NoneViable)?;
+        self.ratings
+            .into_iter()
+            .find(|rating_cont| rating_cont.rating.name == chosen)
+            .ok_or(BestFitError::ChosenNotRated(chosen))
+    }
+}
+
+/// Given a set of ratings, filters out the one with the highest factor.
 /// If multiple have the same, highest factor, the first one is returned.
 ///
 /// # Errors
 ///
-/// If none of the supplied ratings has a factor higher then 0.0.
+/// If no ratings are supplied.
+///
+/// See [`Ranking`] for a more thorough evaluation.
 pub fn best_fit(ratings: Vec<RatingCont>) -> Result<RatingCont, BestFitError> {
     let mut max_rating: Option<RatingCont> = None;
     for rating_cont in ratings {
@@ -162,16 +272,6 @@ pub fn best_fit(ratings: Vec<RatingCont>) -> Result<RatingCont, BestFitError> {
     max_rating.ok_or(BestFitError::NoneViable)
 }
 
-#[derive(Error, Debug)]
-pub enum Error {
-    #[error("Failed to evaluate the best fit, because: {0:?}")]
-    BestFitError(#[from] BestFitError),
-
-    /// Represents all other cases of `std::io::Error`.
-    #[error(transparent)]
-    IO(#[from] std::io::Error),
-}
-
 /// Given a set of the relative paths of all dirs and files in a project,
 /// for each of the known dir standards from
 /// <https://github.com/hoijui/osh-dir-std/>,
@@ -182,10 +282,13 @@ pub enum Error {
 /// # Errors
 ///
 /// If any of the input listing entries is an error,
-/// usually caused by an I/O issue.
+/// usually caused by an I/O issue,
+/// if a standard is unknown or invalid,
+/// or if no standard fits at all in case of [`Standards::BestFit`].
 pub fn rate_listing_by_stds<T>(
     dirs_and_files: T,
     ignored_paths: &Regex,
+    policy: MatchPolicy,
     stds: &Standards,
 ) -> Result<Vec<RatingCont>, Error>
 where
@@ -195,16 +298,43 @@ where
         Standards::Default => vec![rate_listing_with(
             dirs_and_files,
             ignored_paths,
+            policy,
             crate::DEFAULT_STD_NAME,
         )?],
-        Standards::All => rate_listing(dirs_and_files, ignored_paths)?,
-        Standards::BestFit => {
-            let ratings: Vec<RatingCont> = rate_listing(dirs_and_files, ignored_paths)?;
-            let max_rating: RatingCont = best_fit(ratings)?;
-            vec![max_rating]
+        Standards::All => rate_listing(dirs_and_files, ignored_paths, policy)?,
+        Standards::BestFit(thresholds) => {
+            let ratings = rate_listing(dirs_and_files, ignored_paths, policy)?;
+            vec![Ranking::new(ratings, thresholds).into_chosen()?]
         }
         Standards::Specific(std_name) => {
-            vec![rate_listing_with(dirs_and_files, ignored_paths, std_name)?]
+            vec![rate_listing_with(
+                dirs_and_files,
+                ignored_paths,
+                policy,
+                std_name,
+            )?]
         }
     })
 }
+
+/// Rates the listing with all the known standards,
+/// and ranks the results according to the given thresholds.
+///
+/// # Errors
+///
+/// If any of the input listing entries is an error,
+/// usually caused by an I/O issue,
+/// or if the regexes of a standard can not be assembled.
+pub fn rank_listing<T, E>(
+    dirs_and_files: T,
+    ignored_paths: &Regex,
+    policy: MatchPolicy,
+    thresholds: &BestFitThresholds,
+) -> Result<Ranking, Error>
+where
+    T: Iterator<Item = Result<Rc<PathBuf>, E>>,
+    Error: From<E>,
+{
+    let ratings = rate_listing(dirs_and_files, ignored_paths, policy)?;
+    Ok(Ranking::new(ratings, thresholds))
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! The file types that records may expect of their content,
+//! see [`crate::format::Rec::file_types`].
+//!
+//! Each expected file type is either a file extension including the dot
+//! (e.g. `.stl`), matched case-insensitively,
+//! a MIME type (e.g. `application/pdf`),
+//! or a MIME type class (e.g. `model/*`).
+//! MIME types are derived from the file extension
+//! with a small, built-in table.
+
+use std::path::Path;
+
+use crate::format::Rec;
+
+/// The MIME types of the known file extensions (lower-case).
+const MIME_TYPES: &[(&str, &str)] = &[
+    // text & documentation
+    ("adoc", "text/asciidoc"),
+    ("csv", "text/csv"),
+    ("htm", "text/html"),
+    ("html", "text/html"),
+    ("markdown", "text/markdown"),
+    ("md", "text/markdown"),
+    ("rst", "text/x-rst"),
+    ("tex", "text/x-tex"),
+    ("txt", "text/plain"),
+    ("doc", "application/msword"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    ("odt", "application/vnd.oasis.opendocument.text"),
+    ("pdf", "application/pdf"),
+    // source code & scripts
+    ("c", "text/x-c"),
+    ("cc", "text/x-c++"),
+    ("cpp", "text/x-c++"),
+    ("h", "text/x-c"),
+    ("hpp", "text/x-c++"),
+    ("ino", "text/x-arduino"),
+    ("java", "text/x-java"),
+    ("js", "text/javascript"),
+    ("py", "text/x-python"),
+    ("rs", "text/x-rust"),
+    ("scad", "text/x-openscad"),
+    ("sh", "text/x-shellscript"),
+    // data & configuration
+    ("json", "application/json"),
+    ("toml", "application/toml"),
+    ("xml", "application/xml"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    // images
+    ("bmp", "image/bmp"),
+    ("gif", "image/gif"),
+    ("jpeg", "image/jpeg"),
+    ("jpg", "image/jpeg"),
+    ("png", "image/png"),
+    ("svg", "image/svg+xml"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("webp", "image/webp"),
+    // audio & video
+    ("mp3", "audio/mpeg"),
+    ("ogg", "audio/ogg"),
+    ("wav", "audio/wav"),
+    ("mp4", "video/mp4"),
+    ("webm", "video/webm"),
+    // 3D models & CAD
+    ("3mf", "model/3mf"),
+    ("fcstd", "model/x-freecad"),
+    ("glb", "model/gltf-binary"),
+    ("gltf", "model/gltf+json"),
+    ("iges", "model/iges"),
+    ("igs", "model/iges"),
+    ("obj", "model/obj"),
+    ("step", "model/step"),
+    ("stl", "model/stl"),
+    ("stp", "model/step"),
+    // electronics
+    ("kicad_pcb", "application/x-kicad-pcb"),
+    ("kicad_pro", "application/x-kicad-project"),
+    ("kicad_sch", "application/x-kicad-schematic"),
+    ("gbr", "application/vnd.gerber"),
+    // archives
+    ("gz", "application/gzip"),
+    ("tar", "application/x-tar"),
+    ("zip", "application/zip"),
+];
+
+/// The MIME type of a file, derived from its extension,
+/// or `None` if the extension is unknown or missing.
+#[must_use]
+pub fn mime_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    MIME_TYPES
+        .iter()
+        .find(|(known_ext, _mime)| *known_ext == ext)
+        .map(|(_ext, mime)| *mime)
+}
+
+/// Whether the file fits the expected file type,
+/// see the [module documentation](self) for its format.
+#[must_use]
+pub fn fits(expected: &str, path: &Path) -> bool {
+    if let Some(expected_ext) = expected.strip_prefix('.') {
+        return path
+            .extension()
+            .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(expected_ext));
+    }
+    let Some(mime) = mime_type(path) else {
+        return false;
+    };
+    expected.strip_suffix("/*").map_or_else(
+        || mime == expected,
+        |class| mime.split_once('/').is_some_and(|(top, _sub)| top == class),
+    )
+}
+
+/// Whether this is a valid file type expectation,
+/// which is either an extension (`.stl`),
+/// or a MIME type (class) known to [`mime_type`].
+#[must_use]
+pub fn is_known(expected: &str) -> bool {
+    if let Some(ext) = expected.strip_prefix('.') {
+        return !ext.is_empty() && !ext.contains('/');
+    }
+    expected.strip_suffix("/*").map_or_else(
+        || MIME_TYPES.iter().any(|(_ext, mime)| *mime == expected),
+        |class| {
+            MIME_TYPES
+                .iter()
+                .any(|(_ext, mime)| mime.split_once('/').is_some_and(|(top, _sub)| top == class))
+        },
+    )
+}
+
+/// Whether the file fits any of the file types expected by the record.
+///
+/// Records without expectations accept any file,
+/// and so do all records for files without an extension,
+/// because their type can not be determined.
+#[must_use]
+pub fn fits_record(rec: &Rec<'_>, path: &Path) -> bool {
+    match &rec.file_types {
+        Some(file_types) if path.extension().is_some() => {
+            file_types.iter().any(|expected| fits(expected, path))
+        }
+        Some(_) | None => true,
+    }
+}
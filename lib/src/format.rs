@@ -45,6 +45,16 @@ impl From<Option<bool>> for OptBool {
     }
 }
 
+impl From<OptBool> for Option<bool> {
+    fn from(value: OptBool) -> Self {
+        match value {
+            OptBool::False => Some(false),
+            OptBool::True => Some(true),
+            OptBool::None => None,
+        }
+    }
+}
+
 impl OptBool {
     #[must_use]
     pub const fn init_code(&self) -> &str {
@@ -106,6 +116,10 @@ pub struct Rec<'a> {
     pub indicativeness: f32,
     pub variations: Option<Vec<&'a str>>,
     pub regex: Option<RegexEq>,
+    /// The file types expected of the files matching this record,
+    /// or - for a directory - of the files below it;
+    /// see `crate::file_types`.
+    pub file_types: Option<Vec<&'a str>>,
     pub description: &'a str,
     pub sample_content: &'a str,
 }
@@ -130,8 +144,11 @@ fn join_set(col: &HashSet<&str>, token: char) -> String {
     // Calculates the final strings length
     let len = col.iter().fold(0, |sum, entry| sum + entry.len() + 1);
 
-    let mut res = col
-        .iter()
+    // NOTE We sort, to get a reproducible result.
+    let mut sorted = col.iter().collect::<Vec<_>>();
+    sorted.sort_unstable();
+    let mut res = sorted
+        .into_iter()
         .fold(String::with_capacity(len), |mut acc, entry| {
             acc.push_str(entry);
             acc.push(token);
@@ -157,6 +174,7 @@ impl Rec<'_> {
             indicativeness: self.indicativeness,
             variations: self.variations.as_ref().map(|vars| vars.join("|")),
             regex: self.regex.as_ref().map(|reg| reg.0.clone()),
+            file_types: self.file_types.as_ref().map(|types| types.join("|")),
             description: self.description.to_owned(),
             sample_content: self.sample_content.to_owned(),
         }
@@ -169,14 +187,17 @@ impl Rec<'_> {
     /// If this record has neither `variations` nor `regex` set.
     #[must_use]
     pub fn get_regex_str(&self) -> String {
+        self.regex_str()
+            .unwrap_or_else(|| panic!("A record needs to have either variations or regex set!"))
+    }
+
+    /// Returns the regex identifying the path after the ancestor record defined in the standard,
+    /// or `None` if this record has neither `variations` nor `regex` set.
+    #[must_use]
+    pub fn regex_str(&self) -> Option<String> {
         self.variations.as_ref().map_or_else(
-            || {
-                self.regex.as_ref().map_or_else(
-                    || panic!("A record needs to have either variations or regex set!"),
-                    |regex| regex.as_str().to_string(),
-                )
-            },
-            |vars| format!("({})", vars.join("|")),
+            || self.regex.as_ref().map(|regex| regex.as_str().to_string()),
+            |vars| Some(format!("({})", vars.join("|"))),
         )
     }
 }
@@ -186,6 +207,8 @@ impl Rec<'_> {
 /// to JSON with a shortcut, using serde,
 /// without creating and filling an additional struct
 /// just for JSON serialization.
+/// For a full-fidelity, versioned serialization,
+/// use `crate::report::Report` instead.
 impl Serialize for Rec<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -219,8 +242,13 @@ pub struct Record {
     pub indicativeness: f32,
     pub variations: Option<String>,
     /// Regex for the last path part
-    #[serde(with = "serde_regex")]
+    #[serde(with = "serde_regex", default)]
     pub regex: Option<Regex>,
+    /// Expected file types of the content, separated by '|',
+    /// e.g. ".stl|model/*";
+    /// this column is optional.
+    #[serde(default)]
+    pub file_types: Option<String>,
     pub description: String,
     #[serde(rename(serialize = "Sample Content", deserialize = "Sample Content"))]
     pub sample_content: String,
@@ -248,6 +276,7 @@ impl Codify for Record {
             indicativeness: {:#?}_f32,
             variations: {},
             regex: {},
+            file_types: {},
             description: r#"{}"#,
             sample_content: r#"{}"#,
         }}"##,
@@ -265,6 +294,10 @@ impl Codify for Record {
                 .map(|s| s.split('|').collect::<Vec<_>>())
                 .init_code(),
             self.regex.clone().map(RegexEq).init_code(),
+            self.file_types
+                .as_ref()
+                .map(|s| s.split('|').collect::<Vec<_>>())
+                .init_code(),
             self.description,
             self.sample_content,
         ))
@@ -298,11 +331,26 @@ impl Serialize for DirStd {
     }
 }
 
+/// The owned version of [`DirStd`].
+///
+/// It can be read from and written to CSV,
+/// as used in the hoijui/osh-dir-std repo,
+/// and (de)serialized with serde, e.g. to JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirStandard {
     pub name: String,
     pub records: Vec<Record>,
 }
 
+impl From<&DirStd> for DirStandard {
+    fn from(std: &DirStd) -> Self {
+        Self {
+            name: std.name.to_owned(),
+            records: std.records.iter().map(Rec::to_record).collect(),
+        }
+    }
+}
+
 impl PartialEq for DirStandard {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name
@@ -342,33 +390,50 @@ impl DirStandard {
         name: String,
         rdr: &mut csv::Reader<R>,
     ) -> Result<Self, ParseError> {
-        let mut records_raw = vec![];
-        // with this we ensure, that all the records `indicativeness` values
-        // add up to ~= 1.0
-        let mut indicativeness_sum = 0.0_f32;
+        let mut records = vec![];
         for result in rdr.deserialize() {
             let record: Record = result?;
-            indicativeness_sum += record.indicativeness;
-            records_raw.push(record);
+            records.push(record);
             // trace!("{:?}", record);
             // Try this if you don't like each record smushed on one line:
             // trace!("{:#?}", record);
         }
-        let mut records = vec![];
-        for mut record in records_raw {
+        // NOTE Case insensitive matching is optional,
+        //      and applied when assembling the full path regexes,
+        //      see [`crate::matching::MatchPolicy`].
+        let mut std = Self { name, records };
+        std.normalize_indicativeness();
+        Ok(std)
+    }
+
+    /// Scales the records `indicativeness` values,
+    /// so they add up to ~= 1.0.
+    pub fn normalize_indicativeness(&mut self) {
+        let indicativeness_sum = self
+            .records
+            .iter()
+            .map(|record| record.indicativeness)
+            .sum::<f32>();
+        for record in &mut self.records {
             record.indicativeness /= indicativeness_sum;
-            // NOTE We do this to force a case insensitive matching, and for the whole string!
-            //      see <https://github.com/rust-lang/regex/discussions/737#discussioncomment-264790>
-            // if let Some(rgx) = record.regex {
-            //     record.regex = Some(Regex::new(&format!("(?i)^(?:{rgx})$")).expect(
-            //         "This should always be a valid regex, if the original was valid, \
-            //         which it has to be, due to being successfully parsed already",
-            //     ));
-            // }
-            records.push(record);
         }
+    }
 
-        Ok(Self { name, records })
+    /// Writes this directory standard as CSV,
+    /// as it is used in the hoijui/osh-dir-std repo.
+    ///
+    /// # Errors
+    ///
+    /// If writing or serializing a record failed.
+    pub fn to_csv_writer<W: std::io::Write>(
+        &self,
+        wtr: &mut csv::Writer<W>,
+    ) -> Result<(), ParseError> {
+        for record in &self.records {
+            wtr.serialize(record)?;
+        }
+        wtr.flush()?;
+        Ok(())
     }
 
     /// Reads a directory standard from a CSV file,
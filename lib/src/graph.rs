@@ -0,0 +1,461 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Exports the record hierarchy of a standard,
+//! and how a project maps onto it,
+//! as graphs in the Graphviz DOT or the Mermaid (flowchart) format,
+//! e.g. to be embedded in documentation.
+//!
+//! Records are styled by their kind
+//! (module, arbitrary content, generated, normative or optional),
+//! path parts without a record of their own are drawn dotted.
+//! In a mapping, the paths hang off the records they match
+//! (with dashed edges), coloured by their [`Status`];
+//! only the top-most paths are shown,
+//! so e.g. the files below a matched dir are left out.
+
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    io::Write,
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+};
+
+use crate::{
+    format::{DirStd, Rec},
+    matching::MatchPolicy,
+    tree::{self, RNode},
+    Coverage, Error, Status,
+};
+
+/// The languages a graph can be written in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Graphviz DOT, see <https://graphviz.org/doc/info/lang.html>
+    #[default]
+    Dot,
+    /// A Mermaid flowchart, see <https://mermaid.js.org/syntax/flowchart.html>
+    Mermaid,
+}
+
+impl GraphFormat {
+    pub const NAMES: [&'static str; 2] = ["dot", "mermaid"];
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            _ => Err(format!("Unknown graph format: '{s}'")),
+        }
+    }
+}
+
+impl Display for GraphFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Dot => "dot",
+            Self::Mermaid => "mermaid",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How a node is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    /// The standard its self
+    Root,
+    /// A path part without a record of its own
+    Virtual,
+    Module,
+    ArbitraryContent,
+    Generated,
+    Normative,
+    Optional,
+    /// A normative record that matched no path
+    Missing,
+    /// A group of paths that do not belong to a record
+    Category,
+    Path(Status),
+}
+
+impl Class {
+    fn of(rec: &Rec<'_>) -> Self {
+        if rec.module {
+            Self::Module
+        } else if rec.arbitrary_content == Some(true) {
+            Self::ArbitraryContent
+        } else if rec.generated {
+            Self::Generated
+        } else if rec.normative {
+            Self::Normative
+        } else {
+            Self::Optional
+        }
+    }
+
+    /// The name of the Mermaid `classDef`.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Root => "root",
+            Self::Virtual => "virtual",
+            Self::Module => "module",
+            Self::ArbitraryContent => "arbitrary",
+            Self::Generated => "generated",
+            Self::Normative => "normative",
+            Self::Optional => "optional",
+            Self::Missing => "missing",
+            Self::Category => "category",
+            Self::Path(Status::In) => "pathIn",
+            Self::Path(Status::Module) => "pathModule",
+            Self::Path(Status::Out) => "pathOut",
+            Self::Path(Status::Ignored) => "pathIgnored",
+            Self::Path(Status::ArbitraryContent) => "pathArbitrary",
+            Self::Path(Status::GeneratedContent) => "pathGenerated",
+        }
+    }
+
+    const fn dot_attrs(self) -> &'static str {
+        match self {
+            Self::Root => "shape=folder, style=bold",
+            Self::Virtual => r##"style=dotted, fontcolor="#666666""##,
+            Self::Module => r##"shape=box3d, style=filled, fillcolor="#a9d6e5""##,
+            Self::ArbitraryContent => r##"style=filled, fillcolor="#fff3b0""##,
+            Self::Generated => r##"style="filled,dashed", fillcolor="#e0c3fc""##,
+            Self::Normative => r##"style="filled,bold", fillcolor="#b7e4c7""##,
+            Self::Optional => r"style=solid",
+            Self::Missing => r##"style="filled,bold", color="#d00000", fillcolor="#ffccd5""##,
+            Self::Category => r"shape=plaintext",
+            Self::Path(Status::In) => r##"shape=note, color="#2d6a4f""##,
+            Self::Path(Status::Module) => r##"shape=note, color="#0077b6""##,
+            Self::Path(Status::Out) => r##"shape=note, color="#d00000", fontcolor="#d00000""##,
+            Self::Path(Status::Ignored) => r##"shape=note, color="#999999", fontcolor="#999999""##,
+            Self::Path(Status::ArbitraryContent) => r##"shape=note, color="#b08900""##,
+            Self::Path(Status::GeneratedContent) => {
+                r##"shape=note, style=dashed, color="#7b2cbf""##
+            }
+        }
+    }
+
+    const fn mermaid_style(self) -> &'static str {
+        match self {
+            Self::Root => "font-weight:bold,stroke-width:3px",
+            Self::Virtual => "fill:#fff,stroke-dasharray:2 2,color:#666",
+            Self::Module => "fill:#a9d6e5,stroke-width:2px",
+            Self::ArbitraryContent => "fill:#fff3b0",
+            Self::Generated => "fill:#e0c3fc,stroke-dasharray:5 5",
+            Self::Normative => "fill:#b7e4c7,stroke-width:3px",
+            Self::Optional => "fill:#fff",
+            Self::Missing => "fill:#ffccd5,stroke:#d00000,stroke-width:3px",
+            Self::Category => "fill:none,stroke:none",
+            Self::Path(Status::In) => "fill:#fff,stroke:#2d6a4f",
+            Self::Path(Status::Module) => "fill:#fff,stroke:#0077b6",
+            Self::Path(Status::Out) => "fill:#fff,stroke:#d00000,color:#d00000",
+            Self::Path(Status::Ignored) => "fill:#fff,stroke:#999,color:#999",
+            Self::Path(Status::ArbitraryContent) => "fill:#fff,stroke:#b08900",
+            Self::Path(Status::GeneratedContent) => "fill:#fff,stroke:#7b2cbf,stroke-dasharray:5 5",
+        }
+    }
+}
+
+struct Node {
+    label: String,
+    class: Class,
+}
+
+struct Edge {
+    from: usize,
+    to: usize,
+    /// From a record to a path mapped to it,
+    /// as opposed to from a parent to a child record
+    mapping: bool,
+}
+
+struct Graph {
+    title: String,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+impl Graph {
+    const ROOT: usize = 0;
+
+    fn new(title: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            nodes: vec![Node {
+                label: title.to_owned(),
+                class: Class::Root,
+            }],
+            edges: vec![],
+        }
+    }
+
+    fn add_node(&mut self, label: String, class: Class) -> usize {
+        self.nodes.push(Node { label, class });
+        self.nodes.len() - 1
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, mapping: bool) {
+        self.edges.push(Edge { from, to, mapping });
+    }
+
+    /// Adds the record tree of the standard below the root node,
+    /// returning the node index of each record.
+    fn add_std<'a>(&mut self, std: &'a DirStd) -> Result<Vec<(&'a Rec<'static>, usize)>, Error> {
+        let (root, _rec_nodes) = tree::create(std, MatchPolicy::default())?;
+        let mut rec_idxs = vec![];
+        self.add_children(&root, Self::ROOT, &mut rec_idxs);
+        Ok(rec_idxs)
+    }
+
+    fn add_children<'a>(
+        &mut self,
+        node: &RNode<'a>,
+        node_idx: usize,
+        rec_idxs: &mut Vec<(&'a Rec<'static>, usize)>,
+    ) {
+        let node_ref = node.borrow();
+        let mut children = node_ref.children.iter().collect::<Vec<_>>();
+        children.sort_by_key(|(name, _child)| *name);
+        for (name, child) in children {
+            let value = child.borrow().value;
+            let child_idx = match value {
+                Some(rec) => {
+                    let label = if rec.directory {
+                        format!("{name}/")
+                    } else {
+                        name.clone()
+                    };
+                    let idx = self.add_node(label, Class::of(rec));
+                    rec_idxs.push((rec, idx));
+                    idx
+                }
+                None => self.add_node(format!("{name}/"), Class::Virtual),
+            };
+            self.add_edge(node_idx, child_idx, false);
+            self.add_children(child, child_idx, rec_idxs);
+        }
+    }
+
+    /// Adds a node grouping paths that do not belong to a record.
+    fn add_category(&mut self, label: &str) -> usize {
+        let idx = self.add_node(label.to_owned(), Class::Category);
+        self.add_edge(Self::ROOT, idx, false);
+        idx
+    }
+
+    fn set_class(&mut self, idx: usize, class: Class) {
+        if let Some(node) = self.nodes.get_mut(idx) {
+            node.class = class;
+        }
+    }
+
+    fn add_paths(&mut self, parent: usize, paths: Vec<&Path>, class: Class) {
+        for path in paths {
+            let path_idx = self.add_node(path.display().to_string(), class);
+            self.add_edge(parent, path_idx, true);
+        }
+    }
+
+    fn write_dot<W: Write>(&self, mut wtr: W) -> Result<(), Error> {
+        writeln!(wtr, "digraph \"{}\" {{", escape_dot(&self.title))?;
+        writeln!(wtr, "  rankdir=LR;")?;
+        writeln!(wtr, "  node [shape=box, fontname=\"sans-serif\"];")?;
+        for (idx, node) in self.nodes.iter().enumerate() {
+            writeln!(
+                wtr,
+                "  n{idx} [label=\"{}\", {}];",
+                escape_dot(&node.label),
+                node.class.dot_attrs()
+            )?;
+        }
+        for edge in &self.edges {
+            let attrs = if edge.mapping { " [style=dashed]" } else { "" };
+            writeln!(wtr, "  n{} -> n{}{attrs};", edge.from, edge.to)?;
+        }
+        writeln!(wtr, "}}")?;
+        Ok(())
+    }
+
+    fn write_mermaid<W: Write>(&self, mut wtr: W) -> Result<(), Error> {
+        writeln!(wtr, "---")?;
+        writeln!(wtr, "title: \"{}\"", escape_mermaid(&self.title))?;
+        writeln!(wtr, "---")?;
+        writeln!(wtr, "flowchart LR")?;
+        for (idx, node) in self.nodes.iter().enumerate() {
+            writeln!(
+                wtr,
+                "  n{idx}[\"{}\"]:::{}",
+                escape_mermaid(&node.label),
+                node.class.name()
+            )?;
+        }
+        for edge in &self.edges {
+            let arrow = if edge.mapping { "-.->" } else { "-->" };
+            writeln!(wtr, "  n{} {arrow} n{}", edge.from, edge.to)?;
+        }
+        // Only the used classes, in order of appearance
+        let mut classes = vec![];
+        for node in &self.nodes {
+            if !classes.contains(&node.class) {
+                classes.push(node.class);
+            }
+        }
+        for class in classes {
+            writeln!(wtr, "  classDef {} {}", class.name(), class.mermaid_style())?;
+        }
+        Ok(())
+    }
+
+    fn write<W: Write>(&self, format: GraphFormat, wtr: W) -> Result<(), Error> {
+        match format {
+            GraphFormat::Dot => self.write_dot(wtr),
+            GraphFormat::Mermaid => self.write_mermaid(wtr),
+        }
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+/// The paths that are not below any of the other paths, sorted.
+fn top_most<'a, I>(paths: I) -> Vec<&'a Path>
+where
+    I: IntoIterator<Item = &'a Rc<PathBuf>>,
+{
+    let all = paths
+        .into_iter()
+        .map(|path| path.as_path())
+        .collect::<HashSet<_>>();
+    let mut tops = all
+        .iter()
+        .copied()
+        .filter(|path| !path.ancestors().skip(1).any(|anc| all.contains(anc)))
+        .collect::<Vec<_>>();
+    tops.sort_unstable();
+    tops
+}
+
+/// Groups the paths that are neither shown yet nor below a shown path
+/// under a category node.
+fn add_unmatched<'c>(
+    graph: &mut Graph,
+    shown: &mut HashSet<&'c Path>,
+    paths: &'c [Rc<PathBuf>],
+    status: Status,
+    category: &str,
+) {
+    let rest = top_most(
+        paths
+            .iter()
+            .filter(|path| !path.ancestors().any(|anc| shown.contains(anc))),
+    );
+    if !rest.is_empty() {
+        let category_idx = graph.add_category(category);
+        graph.add_paths(category_idx, rest, Class::Path(status));
+    }
+    shown.extend(paths.iter().map(|path| path.as_path()));
+}
+
+/// Writes the record hierarchy of a standard as a graph.
+///
+/// # Errors
+///
+/// If the record tree of the standard can not be created,
+/// see [`tree::create`],
+/// or writing fails.
+pub fn write_std_graph<W: Write>(std: &DirStd, format: GraphFormat, wtr: W) -> Result<(), Error> {
+    let mut graph = Graph::new(std.name);
+    graph.add_std(std)?;
+    graph.write(format, wtr)
+}
+
+/// Writes how a project maps onto a standard as a graph:
+/// The record hierarchy, with the (top-most) paths
+/// attached to the records they match.
+///
+/// Normative records that matched no path are highlighted as missing.
+/// Arbitrary and generated content that is not below a matched dir,
+/// and the paths out of the standard,
+/// hang off extra nodes, one per category.
+/// Ignored paths and the contents of modules are left out.
+///
+/// # Errors
+///
+/// If the record tree of the standard can not be created,
+/// see [`tree::create`],
+/// or writing fails.
+pub fn write_mapping_graph<W: Write>(
+    coverage: &Coverage,
+    format: GraphFormat,
+    wtr: W,
+) -> Result<(), Error> {
+    let mut graph = Graph::new(coverage.std.name);
+    let rec_idxs = graph.add_std(coverage.std)?;
+    let generated = coverage
+        .generated_content
+        .iter()
+        .map(|path| path.as_path())
+        .collect::<HashSet<_>>();
+    let mut shown = HashSet::new();
+
+    for (rec, rec_idx) in &rec_idxs {
+        let Some(paths) = coverage.r#in.get(rec).filter(|paths| !paths.is_empty()) else {
+            if rec.normative {
+                graph.set_class(*rec_idx, Class::Missing);
+            }
+            continue;
+        };
+        for path in top_most(paths) {
+            let status = if rec.module {
+                Status::Module
+            } else if generated.contains(path) {
+                Status::GeneratedContent
+            } else {
+                Status::In
+            };
+            graph.add_paths(*rec_idx, vec![path], Class::Path(status));
+        }
+        shown.extend(paths.iter().map(|path| path.as_path()));
+    }
+
+    // Paths below a matched dir are left out,
+    // even if they are in a different category
+    add_unmatched(
+        &mut graph,
+        &mut shown,
+        &coverage.arbitrary_content,
+        Status::ArbitraryContent,
+        "(arbitrary content)",
+    );
+    add_unmatched(
+        &mut graph,
+        &mut shown,
+        &coverage.generated_content,
+        Status::GeneratedContent,
+        "(generated content)",
+    );
+    let out = top_most(&coverage.out);
+    if !out.is_empty() {
+        let idx = graph.add_category("(out of the standard)");
+        graph.add_paths(idx, out, Class::Path(Status::Out));
+    }
+
+    graph.write(format, wtr)
+}
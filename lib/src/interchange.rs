@@ -0,0 +1,209 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs,
+    io::{Read, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+use crate::format::{DirStandard, DirStd, ParseError, Rec, RegexEq};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+
+    #[error("Failed to read or write standard: {0}")]
+    IO(#[from] std::io::Error),
+
+    #[error("Failed to (de)serialize standard as JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to (de)serialize standard as YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("Failed to serialize standard as TOML: {0}")]
+    TomlSer(#[from] toml::ser::Error),
+
+    #[error("Failed to deserialize standard from TOML: {0}")]
+    TomlDe(#[from] toml::de::Error),
+
+    #[error("Unknown standard format: '{0}'")]
+    UnknownFormat(String),
+}
+
+/// The formats a directory standard can be read from and written to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StdFormat {
+    /// As used in the hoijui/osh-dir-std repo;
+    /// the name of the standard is not part of the data.
+    #[default]
+    Csv,
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl StdFormat {
+    pub const NAMES: [&'static str; 4] = ["csv", "json", "yaml", "toml"];
+
+    /// Guesses the format from a file-name extension.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "yml" => Some(Self::Yaml),
+            _ => ext.parse().ok(),
+        }
+    }
+}
+
+impl FromStr for StdFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "yaml" => Ok(Self::Yaml),
+            "toml" => Ok(Self::Toml),
+            _ => Err(Error::UnknownFormat(s.to_owned())),
+        }
+    }
+}
+
+impl Display for StdFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Leaks a string, to make it live for the rest of the process.
+fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+impl DirStandard {
+    /// Reads a directory standard in the given format.
+    ///
+    /// `name` is only used for [`StdFormat::Csv`],
+    /// as the other formats contain the name.
+    /// The records `indicativeness` values are normalized.
+    ///
+    /// # Errors
+    ///
+    /// If reading or parsing failed.
+    pub fn read<R: Read>(name: String, format: StdFormat, mut rdr: R) -> Result<Self, Error> {
+        let mut std = match format {
+            StdFormat::Csv => {
+                let mut csv_rdr = csv::ReaderBuilder::new().has_headers(true).from_reader(rdr);
+                return Ok(Self::from_csv_reader(name, &mut csv_rdr)?);
+            }
+            StdFormat::Json => serde_json::from_reader::<_, Self>(rdr)?,
+            StdFormat::Yaml => serde_yaml::from_reader::<_, Self>(rdr)?,
+            StdFormat::Toml => {
+                let mut content = String::new();
+                rdr.read_to_string(&mut content)?;
+                toml::from_str::<Self>(&content)?
+            }
+        };
+        std.normalize_indicativeness();
+        Ok(std)
+    }
+
+    /// Reads a directory standard from a file,
+    /// guessing the format from the file extension if none is given.
+    /// For CSV, the name of the standard is taken
+    /// from the name of the parent directory,
+    /// see [`DirStandard::from_csv_file`].
+    ///
+    /// # Errors
+    ///
+    /// If the format is unknown, or reading or parsing failed.
+    pub fn from_file(file: &Path, format: Option<StdFormat>) -> Result<Self, Error> {
+        let file_format = format
+            .or_else(|| StdFormat::from_path(file))
+            .ok_or_else(|| Error::UnknownFormat(file.display().to_string()))?;
+        if file_format == StdFormat::Csv {
+            return Ok(Self::from_csv_file(file)?);
+        }
+        Self::read(String::new(), file_format, fs::File::open(file)?)
+    }
+
+    /// Writes this directory standard in the given format.
+    ///
+    /// # Errors
+    ///
+    /// If serializing or writing failed.
+    pub fn write<W: Write>(&self, format: StdFormat, mut wtr: W) -> Result<(), Error> {
+        match format {
+            StdFormat::Csv => {
+                let mut csv_wtr = csv::Writer::from_writer(wtr);
+                self.to_csv_writer(&mut csv_wtr)?;
+            }
+            StdFormat::Json => {
+                serde_json::to_writer_pretty(&mut wtr, self)?;
+                writeln!(wtr)?;
+            }
+            StdFormat::Yaml => serde_yaml::to_writer(wtr, self)?,
+            StdFormat::Toml => wtr.write_all(toml::to_string(self)?.as_bytes())?,
+        }
+        Ok(())
+    }
+
+    /// Converts this standard into the form used at runtime,
+    /// so it can be used like the built-in ones,
+    /// e.g. with [`crate::Checker::new`].
+    ///
+    /// NOTE This leaks the memory of the standard,
+    /// so only use it for standards that are used
+    /// until the end of the process.
+    #[must_use]
+    pub fn into_static(self) -> &'static DirStd {
+        let records = self
+            .records
+            .into_iter()
+            .map(|record| {
+                let directory = record.directory();
+                let path = leak(record.path);
+                Rec {
+                    path,
+                    normative: record.normative,
+                    tracked: record.tracked,
+                    generated: record.generated,
+                    module: record.module,
+                    directory,
+                    arbitrary_content: record.arbitrary_content.into(),
+                    tags: leak(record.tags).split('|').collect::<HashSet<_>>(),
+                    indicativeness: record.indicativeness,
+                    variations: record
+                        .variations
+                        .map(|vars| leak(vars).split('|').collect::<Vec<_>>()),
+                    regex: record.regex.map(RegexEq),
+                    file_types: record
+                        .file_types
+                        .map(|types| leak(types).split('|').collect::<Vec<_>>()),
+                    description: leak(record.description),
+                    sample_content: leak(record.sample_content),
+                }
+            })
+            .collect();
+        Box::leak(Box::new(DirStd {
+            name: leak(self.name),
+            records,
+        }))
+    }
+}
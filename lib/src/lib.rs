@@ -4,24 +4,57 @@
 
 #![allow(clippy::cast_precision_loss)]
 
+pub mod additions;
+pub mod badge;
+pub mod ci;
 pub mod constants;
 mod coverage;
 pub mod data;
+mod diff;
+pub mod drift;
+mod error;
 mod evaluation;
+pub mod file_types;
 pub mod format;
+pub mod graph;
+pub mod interchange;
+pub mod lint;
+pub mod listing;
+pub mod matching;
+pub mod report;
+pub mod rules;
 pub mod stds;
+pub mod tags;
 pub mod tree;
 
 pub use coverage::cover_listing;
 pub use coverage::cover_listing_by_stds;
 pub use coverage::cover_listing_with;
+pub use coverage::stream_listing_by_stds;
+pub use coverage::Checker;
 pub use coverage::Coverage;
+pub use coverage::CoverageStats;
+pub use coverage::MappingRow;
+pub use coverage::PathMapping;
+pub use coverage::Status;
+pub use diff::diff;
+pub use diff::CoverageDiff;
+pub use drift::check_drift;
+pub use drift::Drift;
+pub use error::Error;
 pub use evaluation::best_fit;
+pub use evaluation::rank_listing;
 pub use evaluation::rate_listing;
 pub use evaluation::rate_listing_by_stds;
 pub use evaluation::rate_listing_with;
+pub use evaluation::BestFitError;
+pub use evaluation::BestFitThresholds;
+pub use evaluation::Ranking;
 pub use evaluation::Rating;
 pub use evaluation::RatingCont;
+pub use evaluation::Verdict;
+pub use listing::ListingSource;
+pub use report::Report;
 
 use git_version::git_version;
 
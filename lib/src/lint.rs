@@ -0,0 +1,356 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs,
+    hash::BuildHasher,
+    path::Path,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{data::STDS, file_types, format::ParseError};
+
+/// A single problem found in a directory standard definition.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Problem {
+    /// The row could not be parsed at all.
+    Malformed { reason: String },
+    /// The `Regex` column, or the regex made up of the `Variations`,
+    /// does not compile.
+    InvalidRegex { regex: String, reason: String },
+    /// The full path regex of this record also matches
+    /// the path of an other record.
+    OverlappingRegex { other_line: u64, other_path: String },
+    /// Both `Variations` and `Regex` are set.
+    BothVariationsAndRegex,
+    /// Neither `Variations` nor `Regex` are set.
+    NeitherVariationsNorRegex,
+    /// There is no record for the parent directory of this record.
+    MissingParent { parent: String },
+    /// `Indicativeness` has to be greater than zero.
+    NonPositiveIndicativeness { indicativeness: f32 },
+    /// An other record has the same path.
+    DuplicatePath { first_line: u64 },
+    /// A tag not used by any of the known standards.
+    UnknownTag { tag: String },
+    /// `Module` is set on a file (a path not ending in '/').
+    ModuleOnFile,
+    /// `Generated` is set on a file (a path not ending in '/').
+    GeneratedOnFile,
+    /// An entry in `FileTypes` that is neither an extension (`.stl`)
+    /// nor a known MIME type (class), see [`crate::file_types`].
+    UnknownFileType { file_type: String },
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed { reason } => write!(f, "malformed row: {reason}"),
+            Self::InvalidRegex { regex, reason } => {
+                write!(f, "invalid regex '{regex}': {reason}")
+            }
+            Self::OverlappingRegex {
+                other_line,
+                other_path,
+            } => write!(
+                f,
+                "regex overlaps with the one of '{other_path}' (line {other_line})"
+            ),
+            Self::BothVariationsAndRegex => write!(f, "has both variations and regex set"),
+            Self::NeitherVariationsNorRegex => write!(f, "has neither variations nor regex set"),
+            Self::MissingParent { parent } => write!(f, "missing parent record '{parent}'"),
+            Self::NonPositiveIndicativeness { indicativeness } => write!(
+                f,
+                "indicativeness has to be greater than zero, but is {indicativeness}"
+            ),
+            Self::DuplicatePath { first_line } => {
+                write!(f, "duplicate path, first defined in line {first_line}")
+            }
+            Self::UnknownTag { tag } => write!(f, "unknown tag '{tag}'"),
+            Self::ModuleOnFile => write!(f, "module flag set on a file"),
+            Self::GeneratedOnFile => write!(f, "generated flag set on a file"),
+            Self::UnknownFileType { file_type } => write!(f, "unknown file type '{file_type}'"),
+        }
+    }
+}
+
+/// A problem, together with where in the CSV it was found.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    /// The line in the CSV file, starting with 1 (the header).
+    pub line: u64,
+    /// The `Path` of the record, if it could be parsed.
+    pub path: String,
+    pub problem: Problem,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: '{}': {}", self.line, self.path, self.problem)
+    }
+}
+
+/// The parts of a [`crate::format::Record`] that are linted,
+/// parsed leniently, so invalid regexes can be reported
+/// instead of failing the whole parsing.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LintRecord {
+    path: String,
+    generated: bool,
+    module: bool,
+    tags: String,
+    indicativeness: f32,
+    variations: Option<String>,
+    regex: Option<String>,
+    #[serde(default)]
+    file_types: Option<String>,
+}
+
+impl LintRecord {
+    fn directory(&self) -> bool {
+        self.path.ends_with('/')
+    }
+
+    /// The path parts, like they are used in [`crate::tree::create`].
+    fn path_parts(&self) -> Vec<&str> {
+        let trimmed = self.path.strip_suffix('/').unwrap_or(&self.path);
+        trimmed.split('/').collect()
+    }
+
+    /// The parent directory record path, if this is not a top-level record.
+    fn parent_path(&self) -> Option<String> {
+        let parts = self.path_parts();
+        parts
+            .split_last()
+            .filter(|(_last, ancestors)| !ancestors.is_empty())
+            .map(|(_last, ancestors)| format!("{}/", ancestors.join("/")))
+    }
+
+    /// The regex for the last path part,
+    /// as in [`crate::format::Rec::get_regex_str`].
+    fn part_regex_str(&self) -> Option<String> {
+        self.variations
+            .as_ref()
+            .map_or_else(|| self.regex.clone(), |vars| Some(format!("({vars})")))
+    }
+
+    /// Example paths that this record is meant to match.
+    fn samples(&self) -> Vec<String> {
+        let mut samples = vec![self.path.trim_end_matches('/').to_owned()];
+        if let Some(vars) = &self.variations {
+            let parent = self.parent_path().unwrap_or_default();
+            samples.extend(vars.split('|').map(|var| format!("{parent}{var}")));
+        }
+        samples
+    }
+}
+
+/// Returns the union of all the tags used in the known standards.
+#[must_use]
+pub fn known_tags() -> HashSet<&'static str> {
+    STDS.values()
+        .flat_map(|std| std.records.iter())
+        .flat_map(|rec| rec.tags.iter().copied())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+fn lint_record<S: BuildHasher>(
+    line: u64,
+    rec: &LintRecord,
+    known_tags: &HashSet<&str, S>,
+    findings: &mut Vec<Finding>,
+) {
+    let mut report = |problem| {
+        findings.push(Finding {
+            line,
+            path: rec.path.clone(),
+            problem,
+        });
+    };
+    match (&rec.variations, &rec.regex) {
+        (Some(_), Some(_)) => report(Problem::BothVariationsAndRegex),
+        (None, None) => report(Problem::NeitherVariationsNorRegex),
+        _ => (),
+    }
+    if let Some(part_regex_str) = rec.part_regex_str() {
+        if let Err(err) = Regex::new(&part_regex_str) {
+            report(Problem::InvalidRegex {
+                regex: part_regex_str,
+                reason: err.to_string(),
+            });
+        }
+    }
+    if rec.indicativeness.is_nan() || rec.indicativeness <= 0.0 {
+        report(Problem::NonPositiveIndicativeness {
+            indicativeness: rec.indicativeness,
+        });
+    }
+    for tag in rec.tags.split('|').filter(|tag| !tag.is_empty()) {
+        if !known_tags.contains(tag) {
+            report(Problem::UnknownTag {
+                tag: tag.to_owned(),
+            });
+        }
+    }
+    for file_type in rec.file_types.iter().flat_map(|types| types.split('|')) {
+        if !file_types::is_known(file_type) {
+            report(Problem::UnknownFileType {
+                file_type: file_type.to_owned(),
+            });
+        }
+    }
+    if !rec.directory() {
+        if rec.module {
+            report(Problem::ModuleOnFile);
+        }
+        if rec.generated {
+            report(Problem::GeneratedOnFile);
+        }
+    }
+}
+
+/// Assembles the full path regex of a record,
+/// like [`crate::tree::create`] does,
+/// or returns `None` if any of its parts is invalid.
+fn full_path_regex(rec: &LintRecord, by_path: &HashMap<&str, &LintRecord>) -> Option<Regex> {
+    let mut bnd_rgx_str = rec.part_regex_str()?;
+    let mut parent_path = rec.parent_path();
+    while let Some(parent) = parent_path {
+        if let Some(parent_rec) = by_path.get(parent.as_str()) {
+            bnd_rgx_str.insert(0, '/');
+            bnd_rgx_str.insert_str(0, &parent_rec.part_regex_str()?);
+            parent_path = parent_rec.parent_path();
+        } else {
+            // NOTE We skip missing ancestors, like the tree does,
+            //      but we need to continue with the grand-parent.
+            let parent_parts = parent.trim_end_matches('/').rsplit_once('/');
+            parent_path = parent_parts.map(|(grand_parent, _)| format!("{grand_parent}/"));
+        }
+    }
+    Regex::new(&format!("^(?:{bnd_rgx_str})$")).ok()
+}
+
+fn lint_structure(recs: &[(u64, LintRecord)], findings: &mut Vec<Finding>) {
+    let mut by_path = HashMap::new();
+    let mut first_lines = HashMap::new();
+    for (line, rec) in recs {
+        if let Some(first_line) = first_lines.get(rec.path.as_str()) {
+            findings.push(Finding {
+                line: *line,
+                path: rec.path.clone(),
+                problem: Problem::DuplicatePath {
+                    first_line: *first_line,
+                },
+            });
+        } else {
+            first_lines.insert(rec.path.as_str(), *line);
+            by_path.insert(rec.path.as_str(), rec);
+        }
+    }
+    for (line, rec) in recs {
+        if let Some(parent) = rec.parent_path() {
+            if !by_path.contains_key(parent.as_str()) {
+                findings.push(Finding {
+                    line: *line,
+                    path: rec.path.clone(),
+                    problem: Problem::MissingParent { parent },
+                });
+            }
+        }
+    }
+    let full_rgxs = recs
+        .iter()
+        .map(|(line, rec)| (*line, rec, full_path_regex(rec, &by_path)))
+        .collect::<Vec<_>>();
+    for (idx, (line, rec, rgx)) in full_rgxs.iter().enumerate() {
+        for (other_line, other_rec, other_rgx) in full_rgxs.iter().take(idx) {
+            if other_rec.path == rec.path || other_rec.directory() != rec.directory() {
+                continue;
+            }
+            let matches_any = |opt_rgx: &Option<Regex>, samples: Vec<String>| {
+                opt_rgx
+                    .as_ref()
+                    .is_some_and(|rgx| samples.iter().any(|sample| rgx.is_match(sample)))
+            };
+            if matches_any(rgx, other_rec.samples()) || matches_any(other_rgx, rec.samples()) {
+                findings.push(Finding {
+                    line: *line,
+                    path: rec.path.clone(),
+                    problem: Problem::OverlappingRegex {
+                        other_line: *other_line,
+                        other_path: other_rec.path.clone(),
+                    },
+                });
+            }
+        }
+    }
+}
+
+/// Checks a directory standard definition in CSV format
+/// for problems that would otherwise only show up as panics
+/// or silently wrong results when using it.
+///
+/// `known_tags` is usually [`known_tags()`].
+/// The findings are sorted by line.
+///
+/// # Errors
+///
+/// If reading the CSV failed,
+/// or its header could not be parsed.
+pub fn lint_csv_reader<R: std::io::Read, S: BuildHasher>(
+    rdr: &mut csv::Reader<R>,
+    known_tags: &HashSet<&str, S>,
+) -> Result<Vec<Finding>, ParseError> {
+    let headers = rdr.headers()?.clone();
+    let path_idx = headers
+        .iter()
+        .position(|header| header == "Path")
+        .unwrap_or_default();
+    let mut findings = vec![];
+    let mut recs = vec![];
+    for result in rdr.records() {
+        let raw_rec = result?;
+        let line = raw_rec.position().map_or(0, csv::Position::line);
+        match raw_rec.deserialize::<LintRecord>(Some(&headers)) {
+            Ok(rec) => {
+                lint_record(line, &rec, known_tags, &mut findings);
+                recs.push((line, rec));
+            }
+            Err(err) => findings.push(Finding {
+                line,
+                path: raw_rec.get(path_idx).unwrap_or_default().to_owned(),
+                problem: Problem::Malformed {
+                    reason: err.to_string(),
+                },
+            }),
+        }
+    }
+    lint_structure(&recs, &mut findings);
+    findings.sort_by_key(|finding| finding.line);
+    Ok(findings)
+}
+
+/// Checks a directory standard definition CSV file,
+/// see [`lint_csv_reader`].
+///
+/// # Errors
+///
+/// If reading the file failed,
+/// or its header could not be parsed.
+pub fn lint_csv_file<S: BuildHasher>(
+    csv_file: &Path,
+    known_tags: &HashSet<&str, S>,
+) -> Result<Vec<Finding>, ParseError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(fs::File::open(csv_file)?);
+    lint_csv_reader(&mut rdr, known_tags)
+}
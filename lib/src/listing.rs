@@ -0,0 +1,414 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// What kind of file-system entry a listed path refers to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    File,
+    #[serde(alias = "directory")]
+    Dir,
+    /// The listing does not tell,
+    /// as is usually the case with plain text listings.
+    #[default]
+    Unknown,
+}
+
+/// A single path of a listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The path, relative to the root of the project.
+    pub path: PathBuf,
+    pub kind: EntryKind,
+}
+
+impl Entry {
+    #[must_use]
+    pub const fn new(path: PathBuf, kind: EntryKind) -> Self {
+        Self { path, kind }
+    }
+
+    /// Creates an entry from a path as it appears in a text listing.
+    /// Removes "./" or ".\" (<- Windows) from the beginning,
+    /// and considers paths ending in a separator to be directories.
+    #[must_use]
+    pub fn from_listed(mut line: String) -> Self {
+        if line.starts_with("./") || line.starts_with(".\\") {
+            line.drain(..2);
+        }
+        let kind = if line.ends_with('/') || line.ends_with('\\') {
+            EntryKind::Dir
+        } else {
+            EntryKind::Unknown
+        };
+        Self {
+            path: PathBuf::from(line),
+            kind,
+        }
+    }
+
+    /// Returns the path, with a trailing separator if it is a dir.
+    /// Further down the line, paths are told apart from files
+    /// only by that separator and the other paths of the listing,
+    /// so this also applies to dirs without any listed content.
+    #[must_use]
+    pub fn into_listed_path(self) -> PathBuf {
+        let mut path = self.path;
+        let has_trailing_sep = path.as_os_str().to_string_lossy().ends_with(['/', '\\']);
+        if self.kind == EntryKind::Dir && !has_trailing_sep && !path.as_os_str().is_empty() {
+            // Pushing an empty path only appends a separator
+            path.push("");
+        }
+        path
+    }
+}
+
+/// A source of the paths of a project,
+/// which can then be checked against the standards,
+/// e.g. with [`crate::cover_listing`].
+///
+/// Any iterator over [`Entry`]s is a listing source,
+/// so custom sources need no more then that.
+/// The adapters in this module cover the common cases:
+/// [`Lines`], [`NulSeparated`], [`JsonLines`], [`FileSystem`]
+/// and [`from_paths`].
+pub trait ListingSource: Iterator<Item = io::Result<Entry>> {
+    /// Returns the listed paths, together with their ancestor dirs,
+    /// as expected by [`crate::cover_listing`] and friends.
+    ///
+    /// In case the listing only contains files,
+    /// we also want to iterate over their ancestor dirs,
+    /// while avoiding duplicate visiting of those.
+    /// As a side-effect, this also filters out duplicate input of any kind,
+    /// file or directory.
+    /// Dirs are marked with a trailing separator,
+    /// see [`Entry::into_listed_path`].
+    /// See [`DirsAdder`] for the meaning of `streaming`.
+    fn dirs_and_files(self, streaming: bool) -> impl Iterator<Item = io::Result<Rc<PathBuf>>>
+    where
+        Self: Sized,
+    {
+        let mut dirs_adder = if streaming {
+            DirsAdder::new_streaming()
+        } else {
+            DirsAdder::new()
+        };
+        self.flat_map(move |entry_res| dirs_adder.add(entry_res.map(Entry::into_listed_path)))
+    }
+}
+
+impl<T: Iterator<Item = io::Result<Entry>>> ListingSource for T {}
+
+/// Adds the ancestor dirs to listed paths,
+/// skipping paths that were already visited.
+///
+/// By default, this creates a cache in memory,
+/// that in the end will usually be as big as the whole listing its self.
+/// Thus in streaming mode, we only compare to the previous path.
+pub struct DirsAdder {
+    /// All the paths visited so far,
+    /// or `None` in streaming mode.
+    visited_dirs_cache: Option<HashSet<Rc<PathBuf>>>,
+    /// The previously visited path,
+    /// only used in streaming mode.
+    last_path: Option<PathBuf>,
+}
+
+impl Default for DirsAdder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DirsAdder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            visited_dirs_cache: Some(HashSet::new()),
+            last_path: None,
+        }
+    }
+
+    /// Creates an adder that uses constant memory,
+    /// by only comparing to the previous path.
+    /// This only avoids duplicates if the listing is sorted,
+    /// or at least has all the content of each dir listed consecutively,
+    /// like the output of `git ls-files` or `find`.
+    #[must_use]
+    pub const fn new_streaming() -> Self {
+        Self {
+            visited_dirs_cache: None,
+            last_path: None,
+        }
+    }
+
+    fn is_new(&mut self, ancestor: &Rc<PathBuf>) -> bool {
+        self.visited_dirs_cache.as_mut().map_or_else(
+            || {
+                self.last_path
+                    .as_ref()
+                    .is_none_or(|last_path| !last_path.starts_with(ancestor.as_path()))
+            },
+            |cache| cache.insert(Rc::clone(ancestor)),
+        )
+    }
+
+    /// Returns the path and those of its ancestors
+    /// that were not visited yet, the path first.
+    /// Errors are passed through.
+    pub fn add<P: AsRef<Path>>(&mut self, path_res: io::Result<P>) -> Vec<io::Result<Rc<PathBuf>>> {
+        #[allow(clippy::option_if_let_else)]
+        if let Ok(path) = path_res {
+            let new_ancestors = path
+                .as_ref()
+                .ancestors()
+                .filter(|ancestor| !ancestor.as_os_str().is_empty())
+                .map(Path::to_path_buf)
+                .map(Rc::new) // We do this to not duplicate memory in cache and the iterator and the coverages
+                .filter(|ancestor| self.is_new(ancestor))
+                .map(Ok)
+                .collect::<Vec<io::Result<_>>>();
+            if self.visited_dirs_cache.is_none() {
+                self.last_path = Some(path.as_ref().to_path_buf());
+            }
+            new_ancestors
+        } else {
+            vec![path_res
+                .map(|path| Path::to_path_buf(path.as_ref()))
+                .map(Rc::new)]
+        }
+    }
+}
+
+/// New-line separated paths;
+/// empty lines and lines starting with '#' are skipped.
+pub struct Lines<R> {
+    lines: io::Lines<R>,
+}
+
+impl<R: BufRead> Lines<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.lines.next()? {
+                Ok(line) if line.is_empty() || line.starts_with('#') => {}
+                line_res => return Some(line_res.map(Entry::from_listed)),
+            }
+        }
+    }
+}
+
+/// NUL ('\0') separated paths,
+/// as produced by `git ls-files -z` or `find -print0`.
+pub struct NulSeparated<R> {
+    parts: io::Split<R>,
+}
+
+impl<R: BufRead> NulSeparated<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            parts: reader.split(b'\0'),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for NulSeparated<R> {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.parts.next()? {
+                Ok(part) if part.is_empty() => {}
+                part_res => {
+                    return Some(part_res.map(|part| {
+                        Entry::from_listed(String::from_utf8_lossy(&part).into_owned())
+                    }))
+                }
+            }
+        }
+    }
+}
+
+/// A single entry of a JSON-lines listing.
+/// Only `path` is required;
+/// additional metadata (e.g. `size`) is accepted.
+#[derive(Deserialize)]
+struct JsonEntry {
+    path: PathBuf,
+    #[serde(default)]
+    kind: EntryKind,
+}
+
+/// One JSON object per line,
+/// with at least a `"path"`, and optionally a `"kind"` field
+/// (`"file"` or `"dir"`).
+pub struct JsonLines<R> {
+    lines: io::Lines<R>,
+}
+
+impl<R: BufRead> JsonLines<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for JsonLines<R> {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => {}
+                line_res => {
+                    return Some(line_res.and_then(|line| {
+                        serde_json::from_str::<JsonEntry>(&line)
+                            .map(|entry| Entry::new(entry.path, entry.kind))
+                            .map_err(|err| {
+                                io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    format!("Bad JSON listing entry '{line}': {err}"),
+                                )
+                            })
+                    }))
+                }
+            }
+        }
+    }
+}
+
+/// All the files and dirs below a root directory,
+/// relative to it, depth-first and sorted within each directory.
+/// Symbolic links are listed as files, and not followed.
+pub struct FileSystem {
+    root: PathBuf,
+    /// The sorted, not yet returned entries of each directory
+    /// along the way from the root to the current one.
+    stack: Vec<std::vec::IntoIter<Entry>>,
+    /// The directory returned last, which is to be descended into next.
+    descend: Option<PathBuf>,
+}
+
+impl FileSystem {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self {
+            root: root.into(),
+            stack: vec![],
+            descend: Some(PathBuf::new()),
+        }
+    }
+
+    fn read_dir(&self, rel_dir: &Path) -> io::Result<Vec<Entry>> {
+        let mut entries = fs::read_dir(self.root.join(rel_dir))?
+            .map(|dir_entry_res| {
+                dir_entry_res.and_then(|dir_entry| {
+                    let kind = if dir_entry.file_type()?.is_dir() {
+                        EntryKind::Dir
+                    } else {
+                        EntryKind::File
+                    };
+                    Ok(Entry::new(rel_dir.join(dir_entry.file_name()), kind))
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by(|entry_a, entry_b| entry_a.path.cmp(&entry_b.path));
+        Ok(entries)
+    }
+}
+
+impl Iterator for FileSystem {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(rel_dir) = self.descend.take() {
+            match self.read_dir(&rel_dir) {
+                Ok(entries) => self.stack.push(entries.into_iter()),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        loop {
+            if let Some(entry) = self.stack.last_mut()?.next() {
+                if entry.kind == EntryKind::Dir {
+                    self.descend = Some(entry.path.clone());
+                }
+                return Some(Ok(entry));
+            }
+            self.stack.pop();
+        }
+    }
+}
+
+/// Creates a listing source from plain paths,
+/// for example the output of a VCS library.
+pub fn from_paths<I, P>(paths: I) -> impl ListingSource
+where
+    I: IntoIterator<Item = P>,
+    P: Into<PathBuf>,
+{
+    paths
+        .into_iter()
+        .map(|path| Ok(Entry::new(path.into(), EntryKind::Unknown)))
+}
+
+/// How the paths in a text listing are separated and encoded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ListingFormat {
+    /// See [`Lines`].
+    #[default]
+    Lines,
+    /// See [`NulSeparated`].
+    Null,
+    /// See [`JsonLines`].
+    JsonLines,
+}
+
+impl ListingFormat {
+    pub const NAMES: [&'static str; 3] = ["lines", "null", "json-lines"];
+
+    /// Creates a listing source that parses the given reader in this format.
+    pub fn source<'r, R: BufRead + 'r>(
+        self,
+        reader: R,
+    ) -> Box<dyn Iterator<Item = io::Result<Entry>> + 'r> {
+        match self {
+            Self::Lines => Box::new(Lines::new(reader)),
+            Self::Null => Box::new(NulSeparated::new(reader)),
+            Self::JsonLines => Box::new(JsonLines::new(reader)),
+        }
+    }
+}
+
+impl FromStr for ListingFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lines" => Ok(Self::Lines),
+            "null" => Ok(Self::Null),
+            "json-lines" => Ok(Self::JsonLines),
+            _ => Err(format!("Unknown listing format: '{s}'")),
+        }
+    }
+}
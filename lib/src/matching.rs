@@ -0,0 +1,63 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// How strictly the paths of a project are matched
+/// against the records of a standard.
+///
+/// The default is the strictest matching,
+/// which is case-sensitive and leaves the paths as they are.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct MatchPolicy {
+    /// Matches paths regardless of upper- or lower-case,
+    /// so e.g. `Doc/` or `README.MD` match `doc/` and `README.md`.
+    /// This applies to the records, module,
+    /// arbitrary- and generated-content regexes,
+    /// but not to the ignored paths regex.
+    pub case_insensitive: bool,
+    /// Brings both the paths and the regexes
+    /// into Unicode Normalization Form C (NFC),
+    /// so that e.g. a decomposed "é" (as commonly produced on macOS)
+    /// matches a composed one.
+    pub unicode_nfc: bool,
+    /// Treats '\' (as used on Windows) as a path separator,
+    /// by converting it to '/' before matching.
+    pub normalize_separators: bool,
+}
+
+impl MatchPolicy {
+    /// Applies this policy to a (relative) path,
+    /// before it gets matched.
+    #[must_use]
+    pub fn normalize_path<'a>(&self, raw_path: &'a str) -> Cow<'a, str> {
+        let mut path = Cow::Borrowed(raw_path);
+        if self.normalize_separators && path.contains('\\') {
+            path = Cow::Owned(path.replace('\\', "/"));
+        }
+        if self.unicode_nfc && !is_nfc(&path) {
+            path = Cow::Owned(path.nfc().collect());
+        }
+        path
+    }
+
+    /// Applies this policy to a (full path) regex,
+    /// before it gets compiled.
+    #[must_use]
+    pub fn adjust_regex(&self, raw_regex_str: &str) -> String {
+        let mut regex_str = if self.unicode_nfc {
+            raw_regex_str.nfc().collect()
+        } else {
+            raw_regex_str.to_owned()
+        };
+        if self.case_insensitive {
+            regex_str.insert_str(0, "(?i)");
+        }
+        regex_str
+    }
+}
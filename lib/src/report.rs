@@ -0,0 +1,217 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    hash::BuildHasher,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    format::{Rec, Record},
+    tags::{self, TagCoverage},
+    Coverage, DEFAULT_STD_NAME, VERSION,
+};
+
+/// The version of the [`Report`] format.
+/// It is increased with every incompatible change to it.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Meta-data about the standard a coverage was checked for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandardInfo {
+    pub name: String,
+    /// Whether this is the default standard.
+    pub default: bool,
+    /// The total number of records in the standard.
+    pub num_records: usize,
+}
+
+/// A record of the standard,
+/// together with all the paths it matched.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordCoverage {
+    pub record: Record,
+    pub paths: Vec<PathBuf>,
+}
+
+/// The owned, fully serializable version of a [`Coverage`].
+///
+/// See the [`Coverage`] fields for documentation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub standard: StandardInfo,
+    /// See [`Coverage::rate`].
+    pub rating: f32,
+    pub num_paths: usize,
+    /// The matched records, sorted by their path.
+    pub records: Vec<RecordCoverage>,
+    pub ignored: Vec<PathBuf>,
+    pub arbitrary_content: Vec<PathBuf>,
+    pub generated_content: Vec<PathBuf>,
+    pub out: Vec<PathBuf>,
+    /// The records expecting certain file types,
+    /// together with the paths of other types matching or below them,
+    /// sorted by record path.
+    #[serde(default)]
+    pub misplaced: Vec<RecordCoverage>,
+    /// The coverage per record tag, see [`tags::tag_coverages`].
+    #[serde(default)]
+    pub tags: Vec<TagCoverage>,
+    pub modules: BTreeMap<PathBuf, Self>,
+}
+
+/// Statistics about the input listing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputStats {
+    /// The number of distinct paths in the listing,
+    /// including the ancestor dirs of the listed paths.
+    pub num_paths: usize,
+    /// How many of those were ignored.
+    pub num_ignored: usize,
+}
+
+/// The result of checking a listing against one or more standards,
+/// in a stable format meant for storing and post-processing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// See [`REPORT_SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// The version of this library that created the report.
+    pub tool_version: String,
+    pub input: InputStats,
+    pub coverages: Vec<CoverageReport>,
+}
+
+fn record_coverages<'a>(
+    recs_paths: impl Iterator<Item = (&'a &'static Rec<'static>, &'a Vec<Rc<PathBuf>>)>,
+) -> Vec<RecordCoverage> {
+    let mut records = recs_paths
+        .filter(|(_rec, paths)| !paths.is_empty())
+        .map(|(rec, paths)| RecordCoverage {
+            record: rec.to_record(),
+            paths: to_owned_paths(paths),
+        })
+        .collect::<Vec<_>>();
+    records.sort_by(|rc_a, rc_b| rc_a.record.path.cmp(&rc_b.record.path));
+    records
+}
+
+fn to_owned_paths(paths: &[Rc<PathBuf>]) -> Vec<PathBuf> {
+    let mut owned = paths
+        .iter()
+        .map(|path| path.as_ref().clone())
+        .collect::<Vec<_>>();
+    owned.sort();
+    owned
+}
+
+impl From<&Coverage> for CoverageReport {
+    fn from(coverage: &Coverage) -> Self {
+        Self {
+            standard: StandardInfo {
+                name: coverage.std.name.to_owned(),
+                default: coverage.std.name == DEFAULT_STD_NAME,
+                num_records: coverage.std.records.len(),
+            },
+            rating: coverage.rate(),
+            num_paths: coverage.num_paths,
+            records: record_coverages(coverage.r#in.iter()),
+            ignored: to_owned_paths(&coverage.ignored),
+            arbitrary_content: to_owned_paths(&coverage.arbitrary_content),
+            generated_content: to_owned_paths(&coverage.generated_content),
+            out: to_owned_paths(&coverage.out),
+            misplaced: record_coverages(coverage.misplaced.iter()),
+            tags: tags::tag_coverages(coverage),
+            modules: coverage
+                .modules
+                .iter()
+                .map(|(mod_path, mod_cov)| (mod_path.clone(), Self::from(mod_cov)))
+                .collect(),
+        }
+    }
+}
+
+impl CoverageReport {
+    /// Collects all paths of this coverage and its modules,
+    /// with the module paths joined onto their module dir,
+    /// so they are relative to the same root as the listing.
+    fn collect_paths(&self, root: &Path, paths: &mut HashSet<PathBuf>) {
+        paths.extend(
+            self.records
+                .iter()
+                .flat_map(|rec_cov| &rec_cov.paths)
+                .chain(&self.ignored)
+                .chain(&self.arbitrary_content)
+                .chain(&self.generated_content)
+                .chain(&self.out)
+                .map(|path| root.join(path)),
+        );
+        for (mod_path, mod_report) in &self.modules {
+            let mod_root = root.join(mod_path);
+            mod_report.collect_paths(&mod_root, paths);
+            paths.insert(mod_root);
+        }
+    }
+
+    fn retain_tags<S: BuildHasher>(&mut self, filter: &HashSet<String, S>) {
+        self.tags
+            .retain(|tag_cov| filter.contains(tag_cov.tag.as_str()));
+        for mod_report in self.modules.values_mut() {
+            mod_report.retain_tags(filter);
+        }
+    }
+
+    fn count_ignored(&self) -> usize {
+        self.ignored.len()
+            + self
+                .modules
+                .values()
+                .map(Self::count_ignored)
+                .sum::<usize>()
+    }
+}
+
+impl Report {
+    /// Creates a report of the coverages of a single listing
+    /// by one or more standards.
+    #[must_use]
+    pub fn new(coverages: &[Coverage]) -> Self {
+        let cov_reports = coverages
+            .iter()
+            .map(CoverageReport::from)
+            .collect::<Vec<_>>();
+        let input = cov_reports.first().map_or(
+            InputStats {
+                num_paths: 0,
+                num_ignored: 0,
+            },
+            |cov_report| {
+                let mut paths = HashSet::new();
+                cov_report.collect_paths(Path::new(""), &mut paths);
+                InputStats {
+                    num_paths: paths.len(),
+                    num_ignored: cov_report.count_ignored(),
+                }
+            },
+        );
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            tool_version: VERSION.to_owned(),
+            input,
+            coverages: cov_reports,
+        }
+    }
+
+    /// Removes the coverages of all tags not in the filter,
+    /// including those of the modules.
+    pub fn retain_tags<S: BuildHasher>(&mut self, filter: &HashSet<String, S>) {
+        for cov_report in &mut self.coverages {
+            cov_report.retain_tags(filter);
+        }
+    }
+}
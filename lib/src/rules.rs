@@ -0,0 +1,448 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! House rules that go beyond what a standard can express,
+//! for example "every module has a `README.md`".
+//!
+//! A [`Rule`] gets the whole listing and its [`Coverage`]
+//! (including the modules), and reports [`Finding`]s.
+//! Rules are collected in a [`Registry`],
+//! which contains the built-in ones and any user-provided ones,
+//! either implemented in Rust or configured in a TOML rules file,
+//! see [`RulesConfig`].
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{ci::Severity, Coverage};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Unknown rule: '{name}'; known rules are: '{}'", .known.join("', '"))]
+    UnknownRule { name: String, known: Vec<String> },
+
+    #[error("Invalid regex in rule '{rule}': {source}")]
+    InvalidRegex {
+        rule: String,
+        #[source]
+        source: regex::Error,
+    },
+
+    #[error("Failed to read rules file: {0}")]
+    IO(#[from] std::io::Error),
+
+    #[error("Failed to parse rules file: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+/// A single violation of a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// The name of the violated rule
+    pub rule: String,
+    pub severity: Severity,
+    /// The offending path, relative to the project root
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl Finding {
+    /// Creates a finding of the given rule,
+    /// with the default severity of that rule.
+    pub fn new<R>(rule: &R, path: PathBuf, message: String) -> Self
+    where
+        R: Rule + ?Sized,
+    {
+        Self {
+            rule: rule.name().to_owned(),
+            severity: rule.severity(),
+            path,
+            message,
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} [{}]",
+            self.path.display(),
+            self.message,
+            self.rule
+        )
+    }
+}
+
+/// A house rule, checked in addition to the standard.
+pub trait Rule {
+    /// A short, unique, machine-readable name, e.g. `module-readme`.
+    fn name(&self) -> &str;
+
+    /// What this rule demands, in one sentence.
+    fn description(&self) -> &str;
+
+    /// The severity of the findings of this rule.
+    fn severity(&self) -> Severity {
+        Severity::Minor
+    }
+
+    /// Checks the listing for violations of this rule.
+    ///
+    /// `listing` contains all listed paths, including their ancestor dirs
+    /// (see [`crate::ListingSource::dirs_and_files`])
+    /// and the ignored ones;
+    /// `coverage` is the coverage of that listing by a standard.
+    fn check(&self, listing: &[Rc<PathBuf>], coverage: &Coverage) -> Vec<Finding>;
+}
+
+/// The paths of the listing that are dirs,
+/// judging by them being the parent of an other listed path.
+fn dirs(listing: &[Rc<PathBuf>]) -> HashSet<&Path> {
+    listing.iter().filter_map(|path| path.parent()).collect()
+}
+
+fn collect_module_dirs(coverage: &Coverage, prefix: &Path, mod_dirs: &mut Vec<PathBuf>) {
+    for (mod_path, mod_coverage) in &coverage.modules {
+        let mod_dir = prefix.join(mod_path);
+        collect_module_dirs(mod_coverage, &mod_dir, mod_dirs);
+        mod_dirs.push(mod_dir);
+    }
+}
+
+/// Every module (and sub-module) has to contain a certain file,
+/// `README.md` by default.
+#[derive(Debug, Clone)]
+pub struct ModuleReadme {
+    pub file_name: String,
+}
+
+impl ModuleReadme {
+    pub const NAME: &'static str = "module-readme";
+    pub const DEFAULT_FILE_NAME: &'static str = "README.md";
+}
+
+impl Default for ModuleReadme {
+    fn default() -> Self {
+        Self {
+            file_name: Self::DEFAULT_FILE_NAME.to_owned(),
+        }
+    }
+}
+
+impl Rule for ModuleReadme {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn description(&self) -> &'static str {
+        "Every module has to contain a README file"
+    }
+
+    fn check(&self, listing: &[Rc<PathBuf>], coverage: &Coverage) -> Vec<Finding> {
+        let listed = listing
+            .iter()
+            .map(|path| path.as_path())
+            .collect::<HashSet<_>>();
+        let mut mod_dirs = vec![];
+        collect_module_dirs(coverage, Path::new(""), &mut mod_dirs);
+        mod_dirs
+            .into_iter()
+            .filter(|mod_dir| !listed.contains(mod_dir.join(&self.file_name).as_path()))
+            .map(|mod_dir| {
+                Finding::new(self, mod_dir, format!("module has no '{}'", self.file_name))
+            })
+            .collect()
+    }
+}
+
+/// Only files matching a whitelist may be placed directly in the project root.
+/// Ignored paths are not checked.
+#[derive(Debug, Clone)]
+pub struct RootFiles {
+    pub allowed: Vec<Regex>,
+}
+
+impl RootFiles {
+    pub const NAME: &'static str = "root-files";
+    /// The file names allowed in the root by default, as regexes.
+    pub const DEFAULT_ALLOWED: &'static [&'static str] = &[
+        r"^(README|LICENSE|LICENCE|COPYING|CHANGELOG|CONTRIBUTING|AUTHORS)(\..+)?$",
+        r"^okh\.toml$",
+    ];
+}
+
+impl Default for RootFiles {
+    fn default() -> Self {
+        Self {
+            allowed: Self::DEFAULT_ALLOWED
+                .iter()
+                .map(|allowed| {
+                    Regex::new(allowed).expect("Static regex is valid; this should never happen")
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Rule for RootFiles {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn description(&self) -> &'static str {
+        "Only whitelisted files may be placed directly in the project root"
+    }
+
+    fn check(&self, listing: &[Rc<PathBuf>], coverage: &Coverage) -> Vec<Finding> {
+        let dirs = dirs(listing);
+        let ignored = coverage
+            .ignored
+            .iter()
+            .map(|path| path.as_path())
+            .collect::<HashSet<_>>();
+        listing
+            .iter()
+            .filter(|path| path.components().count() == 1)
+            .filter(|path| !dirs.contains(path.as_path()) && !ignored.contains(path.as_path()))
+            .filter(|path| {
+                let file_name = path.to_string_lossy();
+                !self
+                    .allowed
+                    .iter()
+                    .any(|allowed| allowed.is_match(&file_name))
+            })
+            .map(|path| {
+                Finding::new(
+                    self,
+                    path.as_ref().clone(),
+                    "file is not allowed in the project root".to_owned(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// A user-defined rule, forbidding paths matching a regex.
+#[derive(Debug, Clone)]
+pub struct Forbid {
+    pub name: String,
+    pub description: String,
+    pub severity: Severity,
+    /// Matched against the whole path, relative to the project root
+    pub paths: Regex,
+}
+
+impl Rule for Forbid {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check(&self, listing: &[Rc<PathBuf>], _coverage: &Coverage) -> Vec<Finding> {
+        listing
+            .iter()
+            .filter(|path| self.paths.is_match(&path.to_string_lossy()))
+            .map(|path| Finding::new(self, path.as_ref().clone(), self.description.clone()))
+            .collect()
+    }
+}
+
+/// The configuration of [`ModuleReadme`] in a rules file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModuleReadmeConfig {
+    pub file_name: Option<String>,
+}
+
+/// The configuration of [`RootFiles`] in a rules file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RootFilesConfig {
+    /// Regexes of the allowed file names,
+    /// replacing [`RootFiles::DEFAULT_ALLOWED`]
+    pub allowed: Option<Vec<String>>,
+}
+
+/// The configuration of a [`Forbid`] rule in a rules file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForbidConfig {
+    pub name: String,
+    pub description: String,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    pub paths: String,
+}
+
+const fn default_severity() -> Severity {
+    Severity::Minor
+}
+
+/// The contents of a TOML rules file,
+/// which enables and configures the built-in rules,
+/// and defines additional ones:
+///
+/// ```toml
+/// [module-readme]
+/// file_name = "README.md"
+///
+/// [root-files]
+/// allowed = ['^README\.md$', '^LICENSE\.txt$', '^okh\.toml$']
+///
+/// [[forbid]]
+/// name = "no-archives"
+/// description = "Archives should be released, not committed"
+/// severity = "major"
+/// paths = '\.(zip|tar\.gz)$'
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct RulesConfig {
+    pub module_readme: Option<ModuleReadmeConfig>,
+    pub root_files: Option<RootFilesConfig>,
+    #[serde(default)]
+    pub forbid: Vec<ForbidConfig>,
+}
+
+impl RulesConfig {
+    /// Reads a rules file.
+    ///
+    /// # Errors
+    ///
+    /// If reading or parsing the file failed.
+    pub fn from_file(file: &Path) -> Result<Self, Error> {
+        Ok(toml::from_str(&fs::read_to_string(file)?)?)
+    }
+}
+
+fn compile(rule: &str, regex: &str) -> Result<Regex, Error> {
+    Regex::new(regex).map_err(|source| Error::InvalidRegex {
+        rule: rule.to_owned(),
+        source,
+    })
+}
+
+/// A collection of rules with unique names,
+/// which are checked together.
+#[derive(Default)]
+pub struct Registry {
+    rules: BTreeMap<String, Box<dyn Rule>>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The names of the built-in rules.
+    pub const BUILTIN: &'static [&'static str] = &[ModuleReadme::NAME, RootFiles::NAME];
+
+    /// Registers a rule with its default settings.
+    ///
+    /// # Errors
+    ///
+    /// If there is no built-in rule with this name.
+    pub fn add_builtin(&mut self, name: &str) -> Result<(), Error> {
+        match name {
+            ModuleReadme::NAME => self.register(Box::new(ModuleReadme::default())),
+            RootFiles::NAME => self.register(Box::new(RootFiles::default())),
+            _ => {
+                return Err(Error::UnknownRule {
+                    name: name.to_owned(),
+                    known: Self::BUILTIN
+                        .iter()
+                        .map(|&known| known.to_owned())
+                        .collect(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers the rules configured in a rules file.
+    ///
+    /// # Errors
+    ///
+    /// If any of the configured regexes is invalid.
+    pub fn add_config(&mut self, config: &RulesConfig) -> Result<(), Error> {
+        if let Some(readme_cfg) = &config.module_readme {
+            let mut rule = ModuleReadme::default();
+            if let Some(file_name) = &readme_cfg.file_name {
+                rule.file_name.clone_from(file_name);
+            }
+            self.register(Box::new(rule));
+        }
+        if let Some(root_cfg) = &config.root_files {
+            let mut rule = RootFiles::default();
+            if let Some(allowed) = &root_cfg.allowed {
+                rule.allowed = allowed
+                    .iter()
+                    .map(|regex| compile(RootFiles::NAME, regex))
+                    .collect::<Result<_, _>>()?;
+            }
+            self.register(Box::new(rule));
+        }
+        for forbid_cfg in &config.forbid {
+            self.register(Box::new(Forbid {
+                name: forbid_cfg.name.clone(),
+                description: forbid_cfg.description.clone(),
+                severity: forbid_cfg.severity,
+                paths: compile(&forbid_cfg.name, &forbid_cfg.paths)?,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Registers a rule,
+    /// replacing any previously registered one with the same name.
+    pub fn register(&mut self, rule: Box<dyn Rule>) {
+        self.rules.insert(rule.name().to_owned(), rule);
+    }
+
+    /// The registered rules, sorted by name.
+    pub fn rules(&self) -> impl Iterator<Item = &dyn Rule> {
+        self.rules.values().map(AsRef::as_ref)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Checks all the registered rules,
+    /// returning their findings sorted by path and rule.
+    #[must_use]
+    pub fn check(&self, listing: &[Rc<PathBuf>], coverage: &Coverage) -> Vec<Finding> {
+        let mut findings = self
+            .rules()
+            .flat_map(|rule| rule.check(listing, coverage))
+            .collect::<Vec<_>>();
+        findings.sort_by(|finding_a, finding_b| {
+            finding_a
+                .path
+                .cmp(&finding_b.path)
+                .then_with(|| finding_a.rule.cmp(&finding_b.rule))
+        });
+        findings
+    }
+}
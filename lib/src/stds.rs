@@ -2,15 +2,31 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::data::DEFAULT_STD_NAME;
+use crate::{
+    data::{DEFAULT_STD_NAME, STDS},
+    evaluation::BestFitThresholds,
+    format::DirStd,
+    Error,
+};
 use std::fmt::Display;
 
+/// Looks up one of the known directory standards by its name.
+///
+/// # Errors
+///
+/// If there is no standard with this name,
+/// suggesting the ones with similar names.
+pub fn by_name(std_name: &str) -> Result<&'static DirStd, Error> {
+    STDS.get(std_name)
+        .ok_or_else(|| Error::unknown_standard(std_name))
+}
+
 #[derive(Default, Clone)]
 pub enum Standards {
     #[default]
     Default,
     All,
-    BestFit,
+    BestFit(BestFitThresholds),
     Specific(String),
 }
 
@@ -19,7 +35,7 @@ impl Display for Standards {
         match self {
             Self::Default => write!(f, "<default>({DEFAULT_STD_NAME})"),
             Self::All => write!(f, "<all>"),
-            Self::BestFit => write!(f, "<best-fit>(...)"),
+            Self::BestFit(_) => write!(f, "<best-fit>(...)"),
             Self::Specific(std_name) => write!(f, "{std_name}"),
         }
     }
@@ -30,7 +46,7 @@ impl Standards {
         let stds = if all {
             Self::All
         } else if best_fit {
-            Self::BestFit
+            Self::BestFit(BestFitThresholds::default())
         } else {
             specific
                 .cloned()
@@ -39,4 +55,14 @@ impl Standards {
         log::info!("Using standard(s): {stds}");
         stds
     }
+
+    /// Sets the thresholds to use in case of [`Self::BestFit`].
+    #[must_use]
+    pub fn with_thresholds(self, thresholds: BestFitThresholds) -> Self {
+        if let Self::BestFit(_) = self {
+            Self::BestFit(thresholds)
+        } else {
+            self
+        }
+    }
 }
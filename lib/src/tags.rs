@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    hash::BuildHasher,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{format::Rec, Coverage};
+
+/// How well the records of a standard with a specific tag
+/// (e.g. "doc" or "source") are covered by a listing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagCoverage {
+    pub tag: String,
+    /// The summed indicativeness of the matched records with this tag,
+    /// divided by that of all the records with this tag;
+    /// 0.0 means none are present, 1.0 means all are.
+    pub factor: f32,
+    /// The number of records with this tag.
+    pub num_records: usize,
+    /// How many of those matched at least one path.
+    pub num_matched_records: usize,
+    /// The paths of the normative records with this tag
+    /// that matched no path, sorted.
+    pub missing_normative: Vec<String>,
+}
+
+/// The tags of a record, sorted and without the empty one.
+#[must_use]
+pub fn record_tags(rec: &Rec<'static>) -> Vec<&'static str> {
+    let mut tags = rec
+        .tags
+        .iter()
+        .copied()
+        .filter(|tag| !tag.is_empty())
+        .collect::<Vec<_>>();
+    tags.sort_unstable();
+    tags
+}
+
+/// Whether any of the tags is in the filter.
+pub fn any_in_filter<'a, I, S>(tags: I, filter: &HashSet<String, S>) -> bool
+where
+    I: IntoIterator<Item = &'a str>,
+    S: BuildHasher,
+{
+    tags.into_iter().any(|tag| filter.contains(tag))
+}
+
+#[derive(Default)]
+struct TagSums {
+    indicativeness: f32,
+    matched_indicativeness: f32,
+    num_records: usize,
+    num_matched_records: usize,
+    missing_normative: Vec<String>,
+}
+
+/// Breaks down the coverage of the root of a project
+/// (not including its modules) per record tag,
+/// sorted by tag.
+///
+/// Paths that are out of the standard belong to no tag,
+/// so unlike [`Coverage::rate`], they do not lower these scores.
+#[must_use]
+pub fn tag_coverages(coverage: &Coverage) -> Vec<TagCoverage> {
+    let mut sums: BTreeMap<&str, TagSums> = BTreeMap::new();
+    for rec in &coverage.std.records {
+        let matched = coverage
+            .r#in
+            .get(rec)
+            .is_some_and(|paths| !paths.is_empty());
+        for tag in record_tags(rec) {
+            let tag_sums = sums.entry(tag).or_default();
+            tag_sums.indicativeness += rec.indicativeness;
+            tag_sums.num_records += 1;
+            if matched {
+                tag_sums.matched_indicativeness += rec.indicativeness;
+                tag_sums.num_matched_records += 1;
+            } else if rec.normative {
+                tag_sums.missing_normative.push(rec.path.to_owned());
+            } else {
+                // Optional records that are missing are fine
+            }
+        }
+    }
+    sums.into_iter()
+        .map(|(tag, mut tag_sums)| {
+            tag_sums.missing_normative.sort();
+            let factor = if tag_sums.indicativeness > 0.0 {
+                tag_sums.matched_indicativeness / tag_sums.indicativeness
+            } else {
+                tag_sums.num_matched_records as f32 / tag_sums.num_records as f32
+            };
+            TagCoverage {
+                tag: tag.to_owned(),
+                factor,
+                num_records: tag_sums.num_records,
+                num_matched_records: tag_sums.num_matched_records,
+                missing_normative: tag_sums.missing_normative,
+            }
+        })
+        .collect()
+}
@@ -4,7 +4,7 @@
 
 use regex::Regex;
 
-use crate::format;
+use crate::{format, matching::MatchPolicy, Error};
 use std::rc::Rc;
 use std::{cell::RefCell, collections::HashMap};
 
@@ -77,13 +77,17 @@ impl Default for Node<'_> {
 
 /// Creates a file-system mimicking, in-memory tree
 /// of the records of a directory standard.
+/// The path regexes of the records are assembled
+/// according to the given matching policy.
 ///
-/// # Panics
+/// # Errors
 ///
-/// If a Record does not have at least one path part, or
-/// if a combined path regex turns out to be malformed.
-#[must_use]
-pub fn create<'a>(std_raw: &'a format::DirStd) -> (RNode<'a>, Vec<RNode<'a>>) {
+/// If a record has neither variations nor a regex set,
+/// or if a combined path regex turns out to be malformed.
+pub fn create<'a>(
+    std_raw: &'a format::DirStd,
+    policy: MatchPolicy,
+) -> Result<(RNode<'a>, Vec<RNode<'a>>), Error> {
     let mut pp_recs: Vec<(Vec<String>, &'a format::Rec<'static>)> = std_raw
         .records
         .iter()
@@ -110,19 +114,27 @@ pub fn create<'a>(std_raw: &'a format::DirStd) -> (RNode<'a>, Vec<RNode<'a>>) {
                 ancestor = Node::add_or_get_child(&ancestor, pp);
             }
         }
-        let lpp = pps
-            .last()
-            .expect("A Record needs to have at least one path part!");
+        let lpp = pps.last().ok_or_else(|| {
+            Error::invalid_standard(std_raw.name, format!("Record without path: '{}'", rec.path))
+        })?;
 
         let leaf = Node::add_or_get_child(&ancestor, lpp);
         let mut leaf_mut = leaf.as_ref().borrow_mut();
         leaf_mut.value = Some(rec);
-        let mut bnd_rgx_str = rec.get_regex_str();
+        let regex_str_of = |rec: &format::Rec| {
+            rec.regex_str().ok_or_else(|| {
+                Error::invalid_standard(
+                    std_raw.name,
+                    format!("Record '{}' has neither variations nor regex set", rec.path),
+                )
+            })
+        };
+        let mut bnd_rgx_str = regex_str_of(rec)?;
         let mut anc = leaf_mut.parent.clone();
         while let Some(ref mut parent) = anc {
             if let Some(parent_val) = parent.borrow().value {
                 bnd_rgx_str.insert(0, '/');
-                bnd_rgx_str.insert_str(0, &parent_val.get_regex_str());
+                bnd_rgx_str.insert_str(0, &regex_str_of(parent_val)?);
             }
             let new_anc = parent.borrow().parent.clone();
             if let Some(anc_c) = anc {
@@ -130,16 +142,22 @@ pub fn create<'a>(std_raw: &'a format::DirStd) -> (RNode<'a>, Vec<RNode<'a>>) {
             }
             anc = new_anc;
         }
-        // NOTE We do this to force a case insensitive matching, and for the whole string!
+        // NOTE We do this to force matching the whole string,
+        //      and optionally (by policy) case insensitive matching,
         //      see <https://github.com/rust-lang/regex/discussions/737#discussioncomment-264790>
         bnd_rgx_str.insert_str(0, "^(?:");
         bnd_rgx_str.insert_str(bnd_rgx_str.len(), ")$");
-        leaf_mut.path_regex = Some(format::RegexEq(
-            Regex::new(&bnd_rgx_str)
-                .unwrap_or_else(|_| panic!("Path regex malformed: '{bnd_rgx_str}'")),
-        ));
+        let adj_rgx_str = policy.adjust_regex(&bnd_rgx_str);
+        leaf_mut.path_regex = Some(format::RegexEq(Regex::new(&adj_rgx_str).map_err(
+            |err| {
+                Error::invalid_standard(
+                    std_raw.name,
+                    format!("Path regex malformed: '{adj_rgx_str}': {err}"),
+                )
+            },
+        )?));
         rec_nodes.push(Rc::clone(&leaf));
     }
 
-    (root, rec_nodes)
+    Ok((root, rec_nodes))
 }
@@ -2,7 +2,12 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use osh_dir_std::{self, format::Rec};
+use std::{path::PathBuf, rc::Rc};
+
+use osh_dir_std::{
+    self, constants, format::Rec, matching::MatchPolicy, BestFitError, BestFitThresholds, Checker,
+    CoverageStats, ListingSource, MappingRow, Ranking, Rating, RatingCont, Report, Verdict,
+};
 
 pub type BoxResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
@@ -31,3 +36,1032 @@ fn prusaish_print_bom() -> BoxResult<()> {
     println!("{rec:?}");
     Ok(())
 }
+
+#[test]
+fn stats_rate_like_coverage() -> BoxResult<()> {
+    let std = osh_dir_std::data::STDS
+        .get(osh_dir_std::DEFAULT_STD_NAME)
+        .ok_or("Default standard not found")?;
+    let listing = [
+        "README.md",
+        "doc",
+        "doc/index.md",
+        "src",
+        "src/main.c",
+        "foo",
+        "foo/bar.txt",
+        ".gitignore",
+    ];
+    let mut covering_checker = Checker::new(
+        std,
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+    )?;
+    let mut classifying_checker = Checker::new(
+        std,
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+    )?;
+    let mut stats = CoverageStats::new(std);
+    for path in listing.map(|path| Rc::new(PathBuf::from(path))) {
+        covering_checker.cover(&path);
+        stats.add(&classifying_checker.classify(&path));
+    }
+    let coverage = covering_checker.coverage();
+    assert_eq!(stats.num_paths, coverage.num_paths);
+    assert_eq!(stats.num_out, coverage.out.len());
+    assert_eq!(stats.num_ignored, coverage.ignored.len());
+    assert!((stats.rate() - coverage.rate()).abs() < f32::EPSILON);
+    // the classifying checker must not have recorded anything
+    assert_eq!(classifying_checker.coverage().num_paths, 0);
+    Ok(())
+}
+
+#[test]
+fn coverage_mappings_like_classified() -> BoxResult<()> {
+    let std = osh_dir_std::data::STDS
+        .get(osh_dir_std::DEFAULT_STD_NAME)
+        .ok_or("Default standard not found")?;
+    let mut listing = [
+        "README.md",
+        "doc",
+        "doc/index.md",
+        "src",
+        "src/main.c",
+        "foo",
+        "foo/bar.txt",
+        ".gitignore",
+    ];
+    listing.sort_unstable();
+    let mut covering_checker = Checker::new(
+        std,
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+    )?;
+    let mut classifying_checker = Checker::new(
+        std,
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+    )?;
+    let mut classified_rows = vec![];
+    for path in listing.map(|path| Rc::new(PathBuf::from(path))) {
+        covering_checker.cover(&path);
+        let mut mapping = classifying_checker.classify(&path);
+        mapping.records.sort_by_key(|rec| rec.path);
+        classified_rows.push(MappingRow::from(&mapping));
+    }
+    let covered_rows = covering_checker
+        .coverage()
+        .mappings()
+        .iter()
+        .map(MappingRow::from)
+        .collect::<Vec<_>>();
+    assert_eq!(covered_rows, classified_rows);
+    Ok(())
+}
+
+#[test]
+fn match_policy_normalizes_paths() -> BoxResult<()> {
+    use unicode_normalization::UnicodeNormalization;
+
+    let std = osh_dir_std::data::STDS
+        .get(osh_dir_std::DEFAULT_STD_NAME)
+        .ok_or("Default standard not found")?;
+    let path = Rc::new(PathBuf::from("ReadMe.MD"));
+
+    let mut strict = Checker::new(
+        std,
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+    )?;
+    assert!(strict.classify(&path).records.is_empty());
+
+    let policy = MatchPolicy {
+        case_insensitive: true,
+        unicode_nfc: true,
+        normalize_separators: true,
+    };
+    let mut lenient = Checker::new(std, &constants::DEFAULT_IGNORED_PATHS, policy)?;
+    assert!(!lenient.classify(&path).records.is_empty());
+
+    let decomposed = "doc\\caf\u{e9}.md".nfd().collect::<String>();
+    assert_eq!(policy.normalize_path(&decomposed), "doc/caf\u{e9}.md");
+    Ok(())
+}
+
+#[test]
+fn lint_std_reports_problems_with_lines() -> BoxResult<()> {
+    use osh_dir_std::lint::{self, Problem};
+
+    let csv = "\
+Path,Normative,Tracked,Generated,Module,ArbitraryContent,Tags,Indicativeness,Variations,Regex,Description,Sample Content
+doc/,true,true,false,false,-,doc,0.5,doc|docs,,Docs,
+doc/index.md,true,true,false,true,-,doc|nonsense,0.0,,,Index,
+src/main.c,true,true,false,false,-,,0.1,,m(ain,Main,
+doc/,true,true,false,false,-,doc,0.5,documentation,,Docs again,
+docs/,true,true,false,false,-,doc,0.5,docs,,Docs plural,
+";
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(csv.as_bytes());
+    let findings = lint::lint_csv_reader(&mut rdr, &lint::known_tags())?;
+    let problems_at = |line| {
+        findings
+            .iter()
+            .filter(|finding| finding.line == line)
+            .map(|finding| finding.problem.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(problems_at(2).is_empty());
+    let line_3 = problems_at(3);
+    assert!(line_3.contains(&Problem::NeitherVariationsNorRegex));
+    assert!(line_3.contains(&Problem::NonPositiveIndicativeness {
+        indicativeness: 0.0
+    }));
+    assert!(line_3.contains(&Problem::UnknownTag {
+        tag: "nonsense".to_owned()
+    }));
+    assert!(line_3.contains(&Problem::ModuleOnFile));
+    let line_4 = problems_at(4);
+    assert!(line_4.contains(&Problem::MissingParent {
+        parent: "src/".to_owned()
+    }));
+    assert!(line_4
+        .iter()
+        .any(|problem| matches!(problem, Problem::InvalidRegex { .. })));
+    assert!(problems_at(5).contains(&Problem::DuplicatePath { first_line: 2 }));
+    assert!(problems_at(6).contains(&Problem::OverlappingRegex {
+        other_line: 2,
+        other_path: "doc/".to_owned()
+    }));
+    Ok(())
+}
+
+#[test]
+fn report_round_trips_through_json() -> BoxResult<()> {
+    let listing = [
+        "README.md",
+        "doc",
+        "doc/index.md",
+        "foo",
+        "foo/bar.txt",
+        ".git",
+        ".git/config",
+    ]
+    .map(|path| Ok::<_, std::io::Error>(Rc::new(PathBuf::from(path))));
+    let coverages = osh_dir_std::cover_listing(
+        listing.into_iter(),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+    )?;
+    let report = Report::new(&coverages);
+    assert_eq!(
+        report.schema_version,
+        osh_dir_std::report::REPORT_SCHEMA_VERSION
+    );
+    assert_eq!(report.tool_version, osh_dir_std::VERSION);
+    assert_eq!(report.coverages.len(), coverages.len());
+    assert_eq!(report.input.num_ignored, 2);
+    assert_eq!(report.input.num_paths, 7);
+    let default_report = report
+        .coverages
+        .iter()
+        .find(|cov_report| cov_report.standard.default)
+        .ok_or("Default standard not in report")?;
+    assert!(default_report
+        .records
+        .iter()
+        .any(|rec_cov| rec_cov.record.path == "README.md"
+            && rec_cov.paths == [PathBuf::from("README.md")]));
+
+    let json = serde_json::to_string(&report)?;
+    let parsed: Report = serde_json::from_str(&json)?;
+    assert_eq!(parsed, report);
+    Ok(())
+}
+
+#[test]
+fn report_counts_module_paths_apart_from_root_paths() -> BoxResult<()> {
+    let listing = [
+        "README.md",
+        "mod/a/README.md",
+        "mod/a/doc/x.md",
+        "src/main.c",
+        "foo.txt",
+    ];
+    let coverage = osh_dir_std::cover_listing_with(
+        osh_dir_std::listing::from_paths(listing).dirs_and_files(true),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        osh_dir_std::stds::by_name("unixish")?,
+    )?;
+    assert!(coverage.modules.contains_key(std::path::Path::new("mod/a")));
+    let report = Report::new(&[coverage]);
+    // README.md, mod, mod/a, mod/a/README.md, mod/a/doc, mod/a/doc/x.md,
+    // src, src/main.c and foo.txt
+    assert_eq!(report.input.num_paths, 9);
+    Ok(())
+}
+
+#[test]
+fn std_round_trips_through_all_formats() -> BoxResult<()> {
+    use osh_dir_std::{format::DirStandard, interchange::StdFormat};
+
+    let std = osh_dir_std::data::STDS
+        .get(osh_dir_std::DEFAULT_STD_NAME)
+        .ok_or("Default standard not found")?;
+    let exported = DirStandard::from(std);
+    for format in [
+        StdFormat::Csv,
+        StdFormat::Json,
+        StdFormat::Yaml,
+        StdFormat::Toml,
+    ] {
+        let mut buffer = vec![];
+        exported.write(format, &mut buffer)?;
+        let imported = DirStandard::read(exported.name.clone(), format, buffer.as_slice())?;
+        assert_eq!(imported.name, exported.name, "{format}");
+        assert_eq!(imported.records.len(), exported.records.len(), "{format}");
+        for (imp, exp) in imported.records.iter().zip(&exported.records) {
+            assert_eq!(imp.path, exp.path, "{format}");
+            assert_eq!(imp.tags, exp.tags, "{format}");
+            assert_eq!(imp.variations, exp.variations, "{format}");
+            assert_eq!(
+                imp.regex.as_ref().map(regex::Regex::as_str),
+                exp.regex.as_ref().map(regex::Regex::as_str),
+                "{format}"
+            );
+            assert!(
+                (imp.indicativeness - exp.indicativeness).abs() < 1e-6,
+                "{format}"
+            );
+        }
+
+        let imported_std = imported.into_static();
+        let mut checker = Checker::new(
+            imported_std,
+            &constants::DEFAULT_IGNORED_PATHS,
+            MatchPolicy::default(),
+        )?;
+        let readme = Rc::new(PathBuf::from("README.md"));
+        assert!(!checker.classify(&readme).records.is_empty(), "{format}");
+    }
+    Ok(())
+}
+
+#[test]
+fn unknown_standard_suggests_similar_names() {
+    let err = osh_dir_std::stds::by_name("unixsh").expect_err("'unixsh' is not a known standard");
+    assert!(matches!(
+        &err,
+        osh_dir_std::Error::UnknownStandard { suggestions, .. }
+            if suggestions.first().map(String::as_str) == Some("unixish")
+    ));
+    assert!(err.to_string().contains("did you mean: 'unixish'"));
+
+    let listing = ["README.md"].map(|path| Ok::<_, std::io::Error>(Rc::new(PathBuf::from(path))));
+    let res = osh_dir_std::rate_listing_with(
+        listing.into_iter(),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        "no-such-std",
+    );
+    assert!(matches!(
+        res,
+        Err(osh_dir_std::Error::UnknownStandard { .. })
+    ));
+}
+
+fn rating(name: &str, factor: f32) -> RatingCont {
+    RatingCont {
+        rating: Rating {
+            name: name.to_owned(),
+            factor,
+        },
+        coverage: None,
+    }
+}
+
+#[test]
+fn best_fit_ranking() {
+    let thresholds = BestFitThresholds::default();
+
+    let clear = Ranking::new(
+        vec![rating("prusaish", 0.3), rating("unixish", 0.6)],
+        &thresholds,
+    );
+    assert_eq!(clear.verdict, Verdict::Clear);
+    assert_eq!(
+        clear.ratings.first().map(|rc| rc.rating.name.as_str()),
+        Some("unixish")
+    );
+    assert_eq!(clear.chosen.as_deref(), Some("unixish"));
+    assert!(!clear.fallback);
+
+    let ambiguous = Ranking::new(
+        vec![rating("prusaish", 0.5), rating("unixish", 0.48)],
+        &thresholds,
+    );
+    assert_eq!(ambiguous.verdict, Verdict::Ambiguous);
+    assert_eq!(
+        ambiguous.chosen.as_deref(),
+        Some(osh_dir_std::DEFAULT_STD_NAME)
+    );
+    assert!(ambiguous.fallback);
+
+    let strict = BestFitThresholds {
+        fallback_to_default: false,
+        ..BestFitThresholds::default()
+    };
+    let none_viable = Ranking::new(
+        vec![rating("prusaish", 0.0), rating("unixish", 0.0)],
+        &strict,
+    );
+    assert_eq!(none_viable.verdict, Verdict::NoneViable);
+    assert!(none_viable.chosen.is_none());
+    assert!(matches!(
+        none_viable.into_chosen(),
+        Err(BestFitError::NoneViable)
+    ));
+
+    let default_unrated = Ranking::new(
+        vec![rating("std-a", 0.5), rating("std-b", 0.5)],
+        &thresholds,
+    );
+    assert!(format!("{default_unrated:?}").contains("std-a"));
+    assert!(matches!(
+        default_unrated.into_chosen(),
+        Err(BestFitError::ChosenNotRated(name)) if name == osh_dir_std::DEFAULT_STD_NAME
+    ));
+}
+
+#[test]
+fn best_fit_coverage_ranks_like_rating() -> BoxResult<()> {
+    use osh_dir_std::stds::Standards;
+
+    let listing = ["README.md", "foo/bar.txt", "foo/baz.txt", "mod/a/README.md"];
+    let dirs_and_files = || osh_dir_std::listing::from_paths(listing).dirs_and_files(false);
+    let thresholds = BestFitThresholds::default();
+    let ranking = osh_dir_std::rank_listing(
+        dirs_and_files(),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        &thresholds,
+    )?;
+    let coverages = osh_dir_std::cover_listing_by_stds(
+        dirs_and_files(),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        &Standards::BestFit(thresholds),
+    )?;
+    let coverage = coverages.first().ok_or("No coverage chosen")?;
+    assert_eq!(Some(coverage.std.name), ranking.chosen.as_deref());
+    let rating = Rating::rate_coverage(coverage);
+    assert!((rating.factor - coverage.rate()).abs() < f32::EPSILON);
+    Ok(())
+}
+
+#[test]
+fn drift_from_declared_standard() -> BoxResult<()> {
+    let okh = std::path::Path::new("okh.toml");
+    let declared = osh_dir_std::drift::parse_declared_std(
+        "name = \"x\"\n[osh-dir-std]\nstandard = \"prusaish\"\n",
+        okh,
+    )?;
+    assert_eq!(declared, "prusaish");
+    assert!(matches!(
+        osh_dir_std::drift::parse_declared_std("name = \"x\"\n", okh),
+        Err(osh_dir_std::Error::InvalidDeclaration { .. })
+    ));
+
+    let ratings = vec![rating("unixish", 0.6), rating("prusaish", 0.3)];
+    let drifted = osh_dir_std::Drift::new(&declared, &ratings, 0.05)?;
+    assert!(drifted.drifted);
+    assert_eq!(drifted.declared.name, "prusaish");
+    assert_eq!(drifted.best.name, "unixish");
+
+    let close = osh_dir_std::Drift::new(&declared, &ratings, 0.5)?;
+    assert!(!close.drifted);
+
+    let on_track = osh_dir_std::Drift::new("unixish", &ratings, 0.05)?;
+    assert!(!on_track.drifted);
+    assert_eq!(on_track.best.name, "unixish");
+    Ok(())
+}
+
+#[test]
+fn listing_sources() -> BoxResult<()> {
+    use osh_dir_std::listing::{EntryKind, FileSystem, ListingFormat};
+
+    let text = "# comment\n./doc/\n\ndoc/README.md\nsrc/main.rs\r\n";
+    let entries = ListingFormat::Lines
+        .source(text.as_bytes())
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries.first().map(|entry| entry.kind),
+        Some(EntryKind::Dir)
+    );
+    let nul = ListingFormat::Null
+        .source(&b"doc/\0doc/README.md\0src/main.rs\0"[..])
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(entries, nul);
+
+    let paths = ListingFormat::Lines
+        .source(text.as_bytes())
+        .dirs_and_files(false)
+        .collect::<Result<Vec<_>, _>>()?;
+    let expected = ["doc", "doc/README.md", "src/main.rs", "src"];
+    assert_eq!(paths, expected.map(|path| Rc::new(PathBuf::from(path))));
+
+    let root = std::env::temp_dir().join(format!("osh-dir-std-listing-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("doc"))?;
+    std::fs::write(root.join("doc/README.md"), "")?;
+    std::fs::write(root.join("LICENSE"), "")?;
+    let fs_entries_res = FileSystem::new(&root).collect::<Result<Vec<_>, _>>();
+    std::fs::remove_dir_all(&root)?;
+    let fs_entries = fs_entries_res?
+        .into_iter()
+        .map(|entry| (entry.path, entry.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fs_entries,
+        vec![
+            (PathBuf::from("LICENSE"), EntryKind::File),
+            (PathBuf::from("doc"), EntryKind::Dir),
+            (PathBuf::from("doc/README.md"), EntryKind::File),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn check_only_added_paths() -> BoxResult<()> {
+    use osh_dir_std::additions::{check_additions, Problem};
+    use std::collections::HashSet;
+
+    let std = osh_dir_std::stds::by_name("unixish")?;
+    let existing = ["README.md", "weird", "weird/a"]
+        .map(PathBuf::from)
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let added = ["weird/b", "strange/c", "strange/d", "build/out.o", "doc"].map(PathBuf::from);
+    let violations = check_additions(
+        std,
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        &existing,
+        added,
+    )?;
+    let found = violations
+        .iter()
+        .map(|violation| {
+            (
+                violation.path.to_string_lossy().into_owned(),
+                &violation.problem,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "build".to_owned(),
+                &Problem::Untracked {
+                    record: "build/".to_owned()
+                }
+            ),
+            ("strange".to_owned(), &Problem::OutOfStandard),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn check_changed_paths_in_context() -> BoxResult<()> {
+    use osh_dir_std::{additions, listing};
+    use std::collections::HashSet;
+
+    let name_status = "M\tsrc/main.c\nA\tDocumentation/x.md\nD\told.txt\nR090\tREADME\tREADME.md\n";
+    let added = additions::parse_name_status(name_status.as_bytes())?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    assert_eq!(added.len(), 2);
+    let name_status_nul =
+        "M\0src/main.c\0A\0Documentation/x.md\0D\0old.txt\0R090\0README\0README.md\0";
+    assert_eq!(
+        additions::parse_name_status_nul(name_status_nul.as_bytes())?
+            .into_iter()
+            .collect::<HashSet<_>>(),
+        added
+    );
+    // git would quote this path without -z
+    assert_eq!(
+        additions::parse_name_status_nul(&b"A\0doc/tab\there.md\0"[..])?,
+        vec![PathBuf::from("doc/tab\there.md")]
+    );
+
+    let std = osh_dir_std::stds::by_name("unixish")?;
+    let all = ["README.md", "Documentation/x.md", "src/main.c"];
+    let misfits = additions::check_changes(
+        std,
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        listing::from_paths(all).dirs_and_files(false),
+        &added,
+    )?;
+    assert_eq!(misfits.len(), 1);
+    let misfit = misfits.first().ok_or("one misfit")?;
+    assert_eq!(misfit.path, PathBuf::from("Documentation/x.md"));
+    assert_eq!(misfit.suggestion.as_deref(), Some("doc/"));
+    Ok(())
+}
+
+#[test]
+fn ci_annotations() -> BoxResult<()> {
+    use osh_dir_std::ci::{self, CheckKind, Problem};
+
+    let listing = ["README.md", "weird.txt", "build/out.o"];
+    let coverage = osh_dir_std::cover_listing_with(
+        osh_dir_std::listing::from_paths(listing).dirs_and_files(false),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        osh_dir_std::stds::by_name("unixish")?,
+    )?;
+    let checks = ci::checks(&coverage);
+    let readme = checks
+        .iter()
+        .find(|check| {
+            check.kind == CheckKind::NormativeRecord
+                && check.path.as_path() == std::path::Path::new("README.md")
+        })
+        .ok_or("README.md is a normative record")?;
+    assert!(readme.problem.is_none());
+    assert!(checks.iter().any(
+        |check| check.path.as_path() == std::path::Path::new("weird.txt")
+            && check.problem == Some(Problem::OutOfStandard)
+    ));
+    assert!(checks.iter().any(
+        |check| check.path.as_path() == std::path::Path::new("build")
+            && matches!(check.problem, Some(Problem::Untracked { .. }))
+    ));
+
+    let mut junit_bytes = vec![];
+    ci::write_junit(&checks, &mut junit_bytes)?;
+    let junit = String::from_utf8(junit_bytes)?;
+    assert!(junit.contains(r#"<testcase classname="unixish.path" name="weird.txt""#));
+
+    let mut github_bytes = vec![];
+    ci::write_github(&checks, &mut github_bytes)?;
+    let github = String::from_utf8(github_bytes)?;
+    assert!(github.contains("::error file=build,"));
+    assert!(!github.contains("file=README.md,"));
+
+    let mut gitlab = vec![];
+    ci::write_gitlab(&checks, &mut gitlab)?;
+    let issues: serde_json::Value = serde_json::from_slice(&gitlab)?;
+    assert_eq!(
+        issues.as_array().map(Vec::len),
+        Some(github.lines().count())
+    );
+    Ok(())
+}
+
+#[test]
+fn rating_badge() {
+    use osh_dir_std::badge::{self, BadgeThresholds};
+
+    let thresholds = BadgeThresholds::default();
+    let svg = badge::rating_badge(
+        badge::DEFAULT_LABEL,
+        &Rating {
+            name: "unixish".to_owned(),
+            factor: 0.871,
+        },
+        thresholds,
+    );
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains("<title>dir-std: unixish 87%</title>"));
+    assert!(svg.contains(badge::COLOR_GOOD));
+
+    assert_eq!(thresholds.color(0.6), badge::COLOR_FAIR);
+    assert_eq!(thresholds.color(0.2), badge::COLOR_POOR);
+    assert!(badge::render("a<b", "c&d", "red").contains("a&lt;b: c&amp;d"));
+}
+
+#[test]
+fn tag_coverages() -> BoxResult<()> {
+    use std::collections::HashSet;
+
+    let listing = ["README.md", "src/main.c"];
+    let coverage = osh_dir_std::cover_listing_with(
+        osh_dir_std::listing::from_paths(listing).dirs_and_files(false),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        osh_dir_std::stds::by_name("unixish")?,
+    )?;
+    let tag_covs = osh_dir_std::tags::tag_coverages(&coverage);
+    let doc = tag_covs
+        .iter()
+        .find(|tag_cov| tag_cov.tag == "doc")
+        .ok_or("unixish has doc records")?;
+    assert_eq!(doc.num_matched_records, 1);
+    assert!(doc.factor > 0.0 && doc.factor < 1.0);
+    assert_eq!(doc.missing_normative, vec!["doc/".to_owned()]);
+    let source = tag_covs
+        .iter()
+        .find(|tag_cov| tag_cov.tag == "source")
+        .ok_or("unixish has source records")?;
+    assert!((source.factor - 1.0).abs() < f32::EPSILON);
+    assert!(source.missing_normative.is_empty());
+
+    let mut report = Report::new(&[coverage]);
+    report.retain_tags(&HashSet::from(["doc".to_owned()]));
+    let tags = report
+        .coverages
+        .first()
+        .ok_or("one coverage")?
+        .tags
+        .iter()
+        .map(|tag_cov| tag_cov.tag.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(tags, vec!["doc"]);
+    Ok(())
+}
+
+#[test]
+fn misplaced_file_types() -> BoxResult<()> {
+    use osh_dir_std::{
+        ci::{self, Problem},
+        format::DirStandard,
+        interchange::StdFormat,
+    };
+
+    let csv = "\
+Path,Normative,Tracked,Generated,Module,ArbitraryContent,Tags,Indicativeness,Variations,Regex,FileTypes,Description,Sample Content
+README.md,true,true,false,false,false,doc,0.5,README.md,,,,
+doc/,true,true,false,false,true,doc,0.3,doc,,.md|image/*|application/pdf,,
+src/,true,true,false,false,true,source,0.4,src,,text/*,,
+";
+    let std = DirStandard::read("typed".to_owned(), StdFormat::Csv, csv.as_bytes())?.into_static();
+    let doc = std
+        .records
+        .iter()
+        .find(|rec| rec.path == "doc/")
+        .ok_or("doc/ record")?;
+    assert_eq!(
+        doc.file_types.as_deref(),
+        Some(&[".md", "image/*", "application/pdf"][..])
+    );
+
+    let listing = [
+        "README.md",
+        "doc/index.MD",
+        "doc/img/photo.jpg",
+        "doc/frame.stl",
+        "src/main.c",
+        "src/datasheet.pdf",
+        "src/Makefile",
+    ];
+    let coverage = osh_dir_std::cover_listing_with(
+        osh_dir_std::listing::from_paths(listing).dirs_and_files(false),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        std,
+    )?;
+    let mut misplaced = coverage
+        .misplaced
+        .iter()
+        .flat_map(|(rec, paths)| paths.iter().map(|path| (rec.path, path.as_path())))
+        .collect::<Vec<_>>();
+    misplaced.sort();
+    assert_eq!(
+        misplaced,
+        vec![
+            ("doc/", std::path::Path::new("doc/frame.stl")),
+            ("src/", std::path::Path::new("src/datasheet.pdf")),
+        ]
+    );
+    assert!(ci::checks(&coverage).iter().any(|check| {
+        check.path.as_path() == std::path::Path::new("doc/frame.stl")
+            && matches!(check.problem, Some(Problem::Misplaced { .. }))
+    }));
+    Ok(())
+}
+
+#[test]
+fn house_rules() -> BoxResult<()> {
+    use osh_dir_std::{
+        ci::{self, Severity},
+        rules::{Registry, RulesConfig},
+    };
+
+    let listing = osh_dir_std::listing::from_paths([
+        "README.md",
+        "notes.txt",
+        "mod/motor/README.md",
+        "mod/pump/doc/index.md",
+        "res/old.zip",
+    ])
+    .dirs_and_files(false)
+    .collect::<Result<Vec<_>, _>>()?;
+    let coverage = osh_dir_std::cover_listing_with(
+        listing.iter().cloned().map(Ok::<_, std::io::Error>),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        osh_dir_std::stds::by_name("unixish")?,
+    )?;
+
+    let config: RulesConfig = toml::from_str(
+        r#"
+        [root-files]
+
+        [[forbid]]
+        name = "no-archives"
+        description = "Archives should be released, not committed"
+        severity = "major"
+        paths = '\.zip$'
+        "#,
+    )?;
+    let mut registry = Registry::new();
+    registry.add_builtin("module-readme")?;
+    registry.add_config(&config)?;
+    assert!(registry.add_builtin("no-such-rule").is_err());
+
+    let findings = registry.check(&listing, &coverage);
+    let found = findings
+        .iter()
+        .map(|finding| (finding.rule.as_str(), finding.path.to_string_lossy()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            ("module-readme", "mod/pump/".into()),
+            ("root-files", "notes.txt".into()),
+            ("no-archives", "res/old.zip".into()),
+        ]
+    );
+    assert_eq!(
+        findings.last().map(|finding| finding.severity),
+        Some(Severity::Major)
+    );
+
+    let checks = ci::rule_checks(coverage.std.name, findings);
+    let mut sarif_bytes = vec![];
+    ci::write_sarif(&checks, &mut sarif_bytes)?;
+    let sarif: serde_json::Value = serde_json::from_slice(&sarif_bytes)?;
+    assert_eq!(
+        sarif.get("version").and_then(serde_json::Value::as_str),
+        Some("2.1.0")
+    );
+    let archive = sarif
+        .pointer("/runs/0/results/2")
+        .ok_or("three SARIF results")?;
+    assert_eq!(
+        archive.get("ruleId").and_then(serde_json::Value::as_str),
+        Some("no-archives")
+    );
+    assert_eq!(
+        archive.get("level").and_then(serde_json::Value::as_str),
+        Some("error")
+    );
+    Ok(())
+}
+
+#[test]
+fn graphs() -> BoxResult<()> {
+    use osh_dir_std::graph::{self, GraphFormat};
+
+    let std = osh_dir_std::stds::by_name("unixish")?;
+    let mut dot_bytes = vec![];
+    graph::write_std_graph(std, GraphFormat::Dot, &mut dot_bytes)?;
+    let dot = String::from_utf8(dot_bytes)?;
+    assert!(dot.starts_with("digraph \"unixish\" {"));
+    assert!(dot.contains(r##"[label="README.md", style="filled,bold", fillcolor="#b7e4c7"]"##));
+    assert!(dot.contains(r#"[label="*/", shape=box3d"#));
+
+    let listing = ["README.md", "res/img/logo.png", "src/main.c", "foo/bar.txt"];
+    let coverage = osh_dir_std::cover_listing_with(
+        osh_dir_std::listing::from_paths(listing).dirs_and_files(false),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        std,
+    )?;
+    let mut mermaid_bytes = vec![];
+    graph::write_mapping_graph(&coverage, GraphFormat::Mermaid, &mut mermaid_bytes)?;
+    let mermaid = String::from_utf8(mermaid_bytes)?;
+    assert!(mermaid.contains("flowchart LR"));
+    // Only the top-most paths are shown
+    assert!(mermaid.contains("[\"res\"]:::pathIn"));
+    assert!(!mermaid.contains("logo.png"));
+    assert!(mermaid.contains("[\"foo\"]:::pathOut"));
+    assert!(!mermaid.contains("bar.txt"));
+    assert!(mermaid.contains("[\"src/main.c\"]:::pathOut"));
+    // A normative record without any matching path
+    assert!(mermaid.contains("[\"doc/\"]:::missing"));
+    assert!(mermaid.contains("classDef missing "));
+    Ok(())
+}
+
+#[test]
+fn diff_coverages() -> BoxResult<()> {
+    let cover = |listing: &[&str], std_name: &str| {
+        osh_dir_std::cover_listing_with(
+            osh_dir_std::listing::from_paths(listing.iter().copied()).dirs_and_files(false),
+            &constants::DEFAULT_IGNORED_PATHS,
+            MatchPolicy::default(),
+            osh_dir_std::stds::by_name(std_name)?,
+        )
+    };
+    let old = cover(&["README.md", "foo.txt", "mod/a/README.md"], "unixish")?;
+    let new = cover(
+        &[
+            "README.md",
+            "LICENSE.txt",
+            "bar.txt",
+            "mod/a/README.md",
+            "mod/a/doc/x.md",
+        ],
+        "unixish",
+    )?;
+
+    let diff = osh_dir_std::diff(&old, &new)?;
+    assert!(!diff.is_empty());
+    assert_eq!(
+        diff.records_matched
+            .iter()
+            .map(|rec| rec.path)
+            .collect::<Vec<_>>(),
+        vec!["LICENSE.txt"]
+    );
+    assert!(diff.records_unmatched.is_empty());
+    assert_eq!(diff.out_added, vec![Rc::new(PathBuf::from("bar.txt"))]);
+    assert_eq!(diff.out_removed, vec![Rc::new(PathBuf::from("foo.txt"))]);
+    assert!(diff.modules_added.is_empty());
+    let mod_diff = diff
+        .modules_changed
+        .get(&PathBuf::from("mod/a"))
+        .ok_or("changed module")?;
+    assert_eq!(
+        mod_diff
+            .records_matched
+            .iter()
+            .map(|rec| rec.path)
+            .collect::<Vec<_>>(),
+        vec!["doc/"]
+    );
+
+    assert!(osh_dir_std::diff(&old, &old)?.is_empty());
+    assert!(matches!(
+        osh_dir_std::diff(&old, &cover(&["README.md"], "prusaish")?),
+        Err(osh_dir_std::Error::StandardMismatch {
+            old: "unixish",
+            new: "prusaish"
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn nul_separated_listing() -> BoxResult<()> {
+    use osh_dir_std::listing::{Entry, EntryKind, NulSeparated};
+
+    // Empty parts (incl. the one after the trailing separator) are skipped,
+    // new-lines and '#' are regular path characters
+    let input = b"README.md\0\0./doc/\0src/main\nfile.c\0#not-a-comment\0";
+    let entries = NulSeparated::new(&input[..]).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        entries,
+        vec![
+            Entry::new("README.md".into(), EntryKind::Unknown),
+            Entry::new("doc/".into(), EntryKind::Dir),
+            Entry::new("src/main\nfile.c".into(), EntryKind::Unknown),
+            Entry::new("#not-a-comment".into(), EntryKind::Unknown),
+        ]
+    );
+    assert_eq!(NulSeparated::new(&b""[..]).count(), 0);
+    Ok(())
+}
+
+#[test]
+fn json_lines_listing() -> BoxResult<()> {
+    use osh_dir_std::listing::{Entry, EntryKind, JsonLines};
+
+    let input = r#"{"path": "README.md", "kind": "file", "size": 42}
+
+  
+{"path": "doc", "kind": "directory"}
+{"path": "src/main.c"}
+"#;
+    let entries = JsonLines::new(input.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        entries,
+        vec![
+            Entry::new("README.md".into(), EntryKind::File),
+            Entry::new("doc".into(), EntryKind::Dir),
+            Entry::new("src/main.c".into(), EntryKind::Unknown),
+        ]
+    );
+
+    // The kind carries through to the classification
+    let input = r#"{"path": "README.md", "kind": "file"}
+{"path": "emptydir", "kind": "dir"}
+{"path": "notes", "kind": "file"}
+"#;
+    let dirs_and_files = JsonLines::new(input.as_bytes())
+        .dirs_and_files(false)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(dirs_and_files.contains(&Rc::new(PathBuf::from("emptydir/"))));
+    let coverage = osh_dir_std::cover_listing_with(
+        dirs_and_files.into_iter().map(Ok::<_, std::io::Error>),
+        &constants::DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        osh_dir_std::stds::by_name("unixish")?,
+    )?;
+    assert_eq!(coverage.out.len(), 2);
+    // Only "notes" is an out file, "emptydir" is an out dir
+    assert_eq!(coverage.num_out_files(), 1);
+
+    // An invalid line is reported, without ending the listing
+    let input = "{\"path\": \"README.md\"}\nREADME.md\n{\"size\": 1}\n{\"path\": \"doc\"}";
+    let results = JsonLines::new(input.as_bytes()).collect::<Vec<_>>();
+    assert_eq!(results.len(), 4);
+    let errors = results
+        .iter()
+        .filter_map(|res| res.as_ref().err())
+        .collect::<Vec<_>>();
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|err| err.kind() == std::io::ErrorKind::InvalidData));
+    assert!(errors
+        .first()
+        .is_some_and(|err| err.to_string().contains("'README.md'")));
+    assert!(matches!(results.last(), Some(Ok(entry)) if entry.path == std::path::Path::new("doc")));
+    Ok(())
+}
+
+#[test]
+fn json_lines_dir_rates_like_text_dir() -> BoxResult<()> {
+    use osh_dir_std::listing::{JsonLines, Lines};
+
+    let std = osh_dir_std::stds::by_name("unixish")?;
+    let rate = |listing: Vec<std::io::Result<Rc<PathBuf>>>| {
+        osh_dir_std::cover_listing_with(
+            listing.into_iter(),
+            &constants::DEFAULT_IGNORED_PATHS,
+            MatchPolicy::default(),
+            std,
+        )
+        .map(|coverage| coverage.rate())
+    };
+    let text = "README.md\nemptydir/\n";
+    let json_lines = "{\"path\": \"README.md\"}\n{\"path\": \"emptydir\", \"kind\": \"dir\"}\n";
+    let text_rating = rate(Lines::new(text.as_bytes()).dirs_and_files(false).collect())?;
+    let json_lines_rating = rate(
+        JsonLines::new(json_lines.as_bytes())
+            .dirs_and_files(false)
+            .collect(),
+    )?;
+    assert!((text_rating - json_lines_rating).abs() < f32::EPSILON);
+    assert!(text_rating > 0.99);
+    Ok(())
+}
+
+#[test]
+fn streaming_out_files_from_listing() -> BoxResult<()> {
+    let std = osh_dir_std::stds::by_name("unixish")?;
+    let out_files = |listing: &[&str]| -> BoxResult<usize> {
+        let stats = osh_dir_std::stream_listing_by_stds(
+            osh_dir_std::listing::from_paths(listing.iter().copied()).dirs_and_files(true),
+            &constants::DEFAULT_IGNORED_PATHS,
+            MatchPolicy::default(),
+            &osh_dir_std::stds::Standards::Specific(std.name.to_owned()),
+            |_mapping| Ok(()),
+        )?;
+        let std_stats = stats.first().ok_or("stats")?;
+        assert_eq!(std_stats.num_out, 5);
+        Ok(std_stats.num_out_files)
+    };
+    // Neither of these exists in the working directory
+    // "foo" and "foo/baz" are out dirs, the rest out files
+    // like `git ls-files`, only listing files
+    assert_eq!(
+        out_files(&["README.md", "foo/bar.txt", "foo/baz/qux.txt", "quux.txt"])?,
+        3
+    );
+    // like `find`, listing each dir before its content
+    assert_eq!(
+        out_files(&[
+            "README.md",
+            "foo",
+            "foo/bar.txt",
+            "foo/baz",
+            "foo/baz/qux.txt",
+            "quux.txt"
+        ])?,
+        3
+    );
+    Ok(())
+}
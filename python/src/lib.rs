@@ -0,0 +1,361 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Python bindings for [`osh_dir_std`],
+//! to rate many listings from Python (e.g. in notebooks)
+//! without spawning the CLI for each of them.
+//!
+//! Build and install into the current Python environment
+//! with [maturin](https://www.maturin.rs/):
+//!
+//! ```shell
+//! cd python
+//! maturin develop --release
+//! ```
+//!
+//! All functions take a listing as a list of paths
+//! (`str` or `pathlib.Path`), relative to the project root,
+//! and return instances of the classes in this module.
+//! The paths of the ancestor dirs are added automatically.
+
+use std::{collections::BTreeMap, path::PathBuf, rc::Rc};
+
+use osh_dir_std::{
+    constants::DEFAULT_IGNORED_PATHS,
+    cover_listing_with,
+    data::STD_NAMES,
+    format::Rec,
+    listing::{self, ListingSource},
+    matching::MatchPolicy,
+    stds, Checker, Coverage as LibCoverage, PathMapping, Rating as LibRating,
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use regex::Regex;
+
+fn to_py_err<E: ToString>(err: &E) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn ignored_paths(regex: Option<&str>) -> PyResult<Regex> {
+    regex.map_or_else(
+        || Ok(DEFAULT_IGNORED_PATHS.clone()),
+        |pattern| Regex::new(pattern).map_err(|err| to_py_err(&err)),
+    )
+}
+
+/// The paths of the listing, together with their ancestor dirs.
+fn dirs_and_files(paths: Vec<PathBuf>) -> PyResult<Vec<Rc<PathBuf>>> {
+    listing::from_paths(paths)
+        .dirs_and_files(false)
+        .collect::<Result<_, _>>()
+        .map_err(|err| to_py_err(&err))
+}
+
+fn to_strings(paths: &[Rc<PathBuf>]) -> Vec<String> {
+    let mut strings = paths
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    strings.sort();
+    strings
+}
+
+/// A single record of a standard,
+/// see <https://github.com/hoijui/osh-dir-std/blob/main/definition_format.csv>.
+#[pyclass(frozen, get_all, module = "osh_dir_std_py")]
+#[derive(Clone)]
+#[allow(clippy::struct_excessive_bools)]
+struct Record {
+    path: String,
+    normative: bool,
+    tracked: bool,
+    generated: bool,
+    module: bool,
+    directory: bool,
+    arbitrary_content: Option<bool>,
+    tags: Vec<String>,
+    indicativeness: f32,
+    variations: Option<Vec<String>>,
+    description: String,
+    sample_content: String,
+}
+
+impl From<&Rec<'_>> for Record {
+    fn from(rec: &Rec<'_>) -> Self {
+        let mut tags = rec
+            .tags
+            .iter()
+            .map(|tag| (*tag).to_owned())
+            .collect::<Vec<_>>();
+        tags.sort();
+        Self {
+            path: rec.path.to_owned(),
+            normative: rec.normative,
+            tracked: rec.tracked,
+            generated: rec.generated,
+            module: rec.module,
+            directory: rec.directory,
+            arbitrary_content: rec.arbitrary_content,
+            tags,
+            indicativeness: rec.indicativeness,
+            variations: rec
+                .variations
+                .as_ref()
+                .map(|variations| variations.iter().map(|var| (*var).to_owned()).collect()),
+            description: rec.description.to_owned(),
+            sample_content: rec.sample_content.to_owned(),
+        }
+    }
+}
+
+#[pymethods]
+impl Record {
+    fn __repr__(&self) -> String {
+        format!("Record('{}')", self.path)
+    }
+}
+
+/// A directory standard, as in `data::STDS` of the Rust library.
+#[pyclass(frozen, get_all, module = "osh_dir_std_py")]
+#[derive(Clone)]
+struct Standard {
+    name: String,
+    records: Vec<Record>,
+}
+
+#[pymethods]
+impl Standard {
+    fn __repr__(&self) -> String {
+        format!("Standard('{}', {} records)", self.name, self.records.len())
+    }
+}
+
+/// How well a listing fits a standard;
+/// 0.0 means not at all, 1.0 means totally/fully.
+#[pyclass(frozen, get_all, module = "osh_dir_std_py")]
+#[derive(Clone)]
+struct Rating {
+    name: String,
+    factor: f32,
+}
+
+impl From<LibRating> for Rating {
+    fn from(rating: LibRating) -> Self {
+        Self {
+            name: rating.name,
+            factor: rating.factor,
+        }
+    }
+}
+
+#[pymethods]
+impl Rating {
+    fn __repr__(&self) -> String {
+        format!("Rating('{}', {})", self.name, self.factor)
+    }
+}
+
+/// Which paths of a listing are covered by which records of a standard.
+#[pyclass(frozen, get_all, module = "osh_dir_std_py")]
+#[derive(Clone)]
+// NOTE `Self` can not be used in the fields, due to the pyclass macro
+#[allow(clippy::use_self)]
+struct Coverage {
+    /// The name of the standard
+    std: String,
+    rating: f32,
+    num_paths: usize,
+    /// The paths matched by each matching record,
+    /// the key being the path of the record
+    /// (called `in` in the Rust library and the JSON output).
+    matched: BTreeMap<String, Vec<String>>,
+    ignored: Vec<String>,
+    arbitrary_content: Vec<String>,
+    generated_content: Vec<String>,
+    out: Vec<String>,
+    /// The paths not of the file types expected by a record,
+    /// the key being the path of that record
+    misplaced: BTreeMap<String, Vec<String>>,
+    /// The coverages of the modules, by module dir.
+    modules: BTreeMap<String, Coverage>,
+}
+
+impl From<&LibCoverage> for Coverage {
+    fn from(coverage: &LibCoverage) -> Self {
+        Self {
+            std: coverage.std.name.to_owned(),
+            rating: coverage.rate(),
+            num_paths: coverage.num_paths,
+            matched: coverage
+                .r#in
+                .iter()
+                .map(|(rec, paths)| (rec.path.to_owned(), to_strings(paths)))
+                .collect(),
+            ignored: to_strings(&coverage.ignored),
+            arbitrary_content: to_strings(&coverage.arbitrary_content),
+            generated_content: to_strings(&coverage.generated_content),
+            out: to_strings(&coverage.out),
+            misplaced: coverage
+                .misplaced
+                .iter()
+                .map(|(rec, paths)| (rec.path.to_owned(), to_strings(paths)))
+                .collect(),
+            modules: coverage
+                .modules
+                .iter()
+                .map(|(mod_path, mod_coverage)| {
+                    (mod_path.to_string_lossy().into_owned(), mod_coverage.into())
+                })
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl Coverage {
+    fn __repr__(&self) -> String {
+        format!("Coverage('{}', rating={})", self.std, self.rating)
+    }
+}
+
+/// Which part of a standard a single path is covered by.
+#[pyclass(frozen, get_all, module = "osh_dir_std_py")]
+#[derive(Clone)]
+struct Mapping {
+    path: String,
+    /// The chain of module dirs that contain this path, outermost first
+    modules: Vec<String>,
+    /// One of `in`, `module`, `out`, `ignored`,
+    /// `arbitrary_content` and `generated_content`.
+    status: String,
+    /// The paths of the matching records
+    records: Vec<String>,
+    generated_content: bool,
+}
+
+impl From<PathMapping> for Mapping {
+    fn from(mapping: PathMapping) -> Self {
+        Self {
+            path: mapping.path.to_string_lossy().into_owned(),
+            modules: mapping
+                .modules
+                .iter()
+                .map(|module| module.to_string_lossy().into_owned())
+                .collect(),
+            status: mapping.status.name().to_owned(),
+            records: mapping
+                .records
+                .iter()
+                .map(|rec| rec.path.to_owned())
+                .collect(),
+            generated_content: mapping.generated_content,
+        }
+    }
+}
+
+#[pymethods]
+impl Mapping {
+    fn __repr__(&self) -> String {
+        format!("Mapping('{}', {})", self.path, self.status)
+    }
+}
+
+/// Returns all the known standards, by name.
+#[pyfunction]
+fn standards() -> PyResult<BTreeMap<String, Standard>> {
+    STD_NAMES
+        .iter()
+        .map(|std_name| {
+            let std = stds::by_name(std_name).map_err(|err| to_py_err(&err))?;
+            Ok((
+                std.name.to_owned(),
+                Standard {
+                    name: std.name.to_owned(),
+                    records: std.records.iter().map(Record::from).collect(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Rates the listing with all the known standards, best fit first.
+#[pyfunction]
+#[pyo3(signature = (paths, ignored_paths_regex=None))]
+#[allow(clippy::needless_pass_by_value)]
+fn rate_listing(paths: Vec<PathBuf>, ignored_paths_regex: Option<&str>) -> PyResult<Vec<Rating>> {
+    let mut ratings = osh_dir_std::rate_listing(
+        dirs_and_files(paths)?
+            .into_iter()
+            .map(Ok::<_, std::io::Error>),
+        &ignored_paths(ignored_paths_regex)?,
+        MatchPolicy::default(),
+    )
+    .map_err(|err| to_py_err(&err))?
+    .into_iter()
+    .map(|rating_cont| Rating::from(rating_cont.rating))
+    .collect::<Vec<_>>();
+    ratings.sort_by(|rating_a, rating_b| rating_b.factor.total_cmp(&rating_a.factor));
+    Ok(ratings)
+}
+
+/// Checks which paths of the listing are covered by which records of the standard.
+#[pyfunction(name = "cover_listing_with")]
+#[pyo3(signature = (paths, standard, ignored_paths_regex=None))]
+#[allow(clippy::needless_pass_by_value)]
+fn cover_listing_with_std(
+    paths: Vec<PathBuf>,
+    standard: &str,
+    ignored_paths_regex: Option<&str>,
+) -> PyResult<Coverage> {
+    let std = stds::by_name(standard).map_err(|err| to_py_err(&err))?;
+    let coverage = cover_listing_with(
+        dirs_and_files(paths)?
+            .into_iter()
+            .map(Ok::<_, std::io::Error>),
+        &ignored_paths(ignored_paths_regex)?,
+        MatchPolicy::default(),
+        std,
+    )
+    .map_err(|err| to_py_err(&err))?;
+    Ok(Coverage::from(&coverage))
+}
+
+/// Classifies each path of the listing (including the ancestor dirs)
+/// according to the standard, in the order of the listing.
+#[pyfunction]
+#[pyo3(signature = (paths, standard, ignored_paths_regex=None))]
+#[allow(clippy::needless_pass_by_value)]
+fn classify(
+    paths: Vec<PathBuf>,
+    standard: &str,
+    ignored_paths_regex: Option<&str>,
+) -> PyResult<Vec<Mapping>> {
+    let std = stds::by_name(standard).map_err(|err| to_py_err(&err))?;
+    let mut checker = Checker::new(
+        std,
+        &ignored_paths(ignored_paths_regex)?,
+        MatchPolicy::default(),
+    )
+    .map_err(|err| to_py_err(&err))?;
+    Ok(dirs_and_files(paths)?
+        .iter()
+        .map(|dir_or_file| Mapping::from(checker.cover(dir_or_file)))
+        .collect())
+}
+
+#[pymodule]
+fn osh_dir_std_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("VERSION", osh_dir_std::VERSION)?;
+    module.add("DEFAULT_STANDARD", osh_dir_std::DEFAULT_STD_NAME)?;
+    module.add_class::<Record>()?;
+    module.add_class::<Standard>()?;
+    module.add_class::<Rating>()?;
+    module.add_class::<Coverage>()?;
+    module.add_class::<Mapping>()?;
+    module.add_function(wrap_pyfunction!(standards, module)?)?;
+    module.add_function(wrap_pyfunction!(rate_listing, module)?)?;
+    module.add_function(wrap_pyfunction!(cover_listing_with_std, module)?)?;
+    module.add_function(wrap_pyfunction!(classify, module)?)?;
+    Ok(())
+}
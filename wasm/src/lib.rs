@@ -0,0 +1,116 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! JavaScript bindings for [`osh_dir_std`],
+//! for use in the browser (or node.js) through WebAssembly,
+//! for example to check the file list of a dragged-in folder or ZIP,
+//! without a server.
+//!
+//! Build with [wasm-pack](https://rustwasm.github.io/wasm-pack/):
+//!
+//! ```shell
+//! wasm-pack build wasm --target web
+//! ```
+//!
+//! All functions return JSON strings,
+//! with the same schema as the output of the CLI,
+//! so they can be processed with `JSON.parse`.
+//! Listings are new-line separated paths,
+//! with directories optionally ending in '/'.
+//! The `standard` argument is the name of a standard, `all` or `best-fit`,
+//! and defaults to the default standard.
+
+use std::io;
+
+use osh_dir_std::{
+    constants::DEFAULT_IGNORED_PATHS,
+    cover_listing_by_stds,
+    data::STD_NAMES,
+    format::DirStandard,
+    listing::{Lines, ListingSource},
+    matching::MatchPolicy,
+    rate_listing, rate_listing_by_stds,
+    stds::{self, Standards},
+    BestFitThresholds, Ranking, RatingCont, Report,
+};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+fn standards(standard: Option<String>) -> Standards {
+    match standard.as_deref() {
+        None => Standards::Default,
+        Some("all") => Standards::All,
+        Some("best-fit") => Standards::BestFit(BestFitThresholds::default()),
+        Some(_) => standard.map_or(Standards::Default, Standards::Specific),
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, JsError> {
+    Ok(serde_json::to_string(value)?)
+}
+
+/// Returns the name of the default standard.
+#[wasm_bindgen(js_name = defaultStandard)]
+#[must_use]
+pub fn default_standard() -> String {
+    osh_dir_std::DEFAULT_STD_NAME.to_owned()
+}
+
+/// Returns all the known standards, including their records,
+/// as a JSON array, each in the format of `osh-dir-std show --format json`.
+///
+/// # Errors
+///
+/// If serializing to JSON failed.
+#[wasm_bindgen(js_name = listStandards)]
+pub fn list_standards() -> Result<String, JsError> {
+    let stds = STD_NAMES
+        .iter()
+        .map(|std_name| stds::by_name(std_name).map(DirStandard::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    to_json(&stds)
+}
+
+/// Rates the listing with the standard(s),
+/// like `osh-dir-std rate`.
+///
+/// # Errors
+///
+/// If the standard is unknown,
+/// or none fits in case of `best-fit`.
+#[wasm_bindgen]
+pub fn rate(listing: &str, standard: Option<String>) -> Result<String, JsError> {
+    let dirs_and_files = Lines::new(io::Cursor::new(listing)).dirs_and_files(false);
+    let stds = standards(standard);
+    let ignored_paths = &DEFAULT_IGNORED_PATHS;
+    if let Standards::BestFit(thresholds) = &stds {
+        let ratings = rate_listing(dirs_and_files, ignored_paths, MatchPolicy::default())?;
+        return to_json(&Ranking::new(ratings, thresholds).remove_coverages());
+    }
+    let ratings =
+        rate_listing_by_stds(dirs_and_files, ignored_paths, MatchPolicy::default(), &stds)?
+            .into_iter()
+            .map(RatingCont::remove_coverage)
+            .collect::<Vec<_>>();
+    to_json(&ratings)
+}
+
+/// Maps each path of the listing to the records of the standard(s),
+/// like `osh-dir-std map --format json`.
+///
+/// # Errors
+///
+/// If the standard is unknown,
+/// or none fits in case of `best-fit`.
+#[wasm_bindgen]
+pub fn map(listing: &str, standard: Option<String>) -> Result<String, JsError> {
+    let dirs_and_files = Lines::new(io::Cursor::new(listing)).dirs_and_files(false);
+    let coverages = cover_listing_by_stds(
+        dirs_and_files,
+        &DEFAULT_IGNORED_PATHS,
+        MatchPolicy::default(),
+        &standards(standard),
+    )?;
+    to_json(&Report::new(&coverages))
+}
@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: 2026 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+// The dependencies of the library part are not all used here
+#![allow(unused_crate_dependencies)]
+
+use osh_dir_std::{format::DirStandard, Report};
+use osh_dir_std_wasm::{default_standard, list_standards, map, rate};
+use serde_json::Value;
+
+// NOTE Only the success paths can be tested natively,
+//      because creating a `JsError` requires a JavaScript host.
+#[test]
+fn same_json_as_the_cli() {
+    let listing = "README.md\nLICENSE.txt\ndoc/\nsrc/main.rs\n";
+
+    let ratings: Value = serde_json::from_str(&rate(listing, None).unwrap()).unwrap();
+    assert_eq!(
+        ratings.pointer("/0/rating/name").and_then(Value::as_str),
+        Some(default_standard().as_str())
+    );
+
+    let report: Report =
+        serde_json::from_str(&map(listing, Some("all".to_owned())).unwrap()).unwrap();
+    assert_eq!(report.coverages.len(), osh_dir_std::data::STD_NAMES.len());
+
+    let stds: Vec<DirStandard> = serde_json::from_str(&list_standards().unwrap()).unwrap();
+    assert!(stds.iter().any(|std| std.name == default_standard()));
+}
+
+/// The paths do not exist, like in the browser,
+/// where there is no file-system to look at.
+#[test]
+fn out_files_lower_the_rating() {
+    let factor = |listing: &str| {
+        let ratings: Value =
+            serde_json::from_str(&rate(listing, Some("unixish".to_owned())).unwrap()).unwrap();
+        ratings
+            .pointer("/0/rating/factor")
+            .and_then(Value::as_f64)
+            .unwrap()
+    };
+    let conforming = factor("README.md\nLICENSE.txt\ndoc/index.md\n");
+    let with_out_files = factor("README.md\nLICENSE.txt\ndoc/index.md\nfoo.txt\nbar/baz.txt\n");
+    assert!(conforming > with_out_files);
+    // Out dirs do not count, only out files
+    let with_out_dir = factor("README.md\nLICENSE.txt\ndoc/index.md\nfoo.txt\nbar/\n");
+    assert!(with_out_dir > with_out_files);
+}